@@ -10,10 +10,7 @@ fn bench_res(c: &mut Criterion, name: &str) {
     c.bench_function(name, |b| b.iter(|| layout.clone().solve(usize::MAX, ())));
 
     let swapped_name = format!("{}-swapped", name);
-    let swapped = Layout {
-        cols: layout.rows,
-        rows: layout.cols,
-    };
+    let swapped = layout.transpose();
     c.bench_function(&swapped_name, |b| {
         b.iter(|| swapped.clone().solve(usize::MAX, ()))
     });
@@ -39,5 +36,22 @@ fn flower(c: &mut Criterion) {
     bench_res(c, "flower");
 }
 
-criterion_group!(res, apple, apple_color, palm, palm_color, flower);
+fn palm_color_sharded(c: &mut Criterion) {
+    let json = read_to_string("res/palm-color.json").unwrap();
+    let layout: Layout<char> = serde_json::from_str(&json).unwrap();
+
+    c.bench_function("palm-color-sharded", |b| {
+        b.iter(|| layout.clone().solve_sharded(usize::MAX, ()))
+    });
+}
+
+criterion_group!(
+    res,
+    apple,
+    apple_color,
+    palm,
+    palm_color,
+    flower,
+    palm_color_sharded
+);
 criterion_main!(res);
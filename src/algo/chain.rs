@@ -1,4 +1,5 @@
 use crate::algo::{Error, PartCell};
+use crate::min_gap;
 use std::ops::Range;
 
 /// Metadata about a chain of boxes.
@@ -56,20 +57,27 @@ impl<T: Copy + PartialEq> Chain<T> {
         start..end
     }
 
+    /// Returns the amount of cells which must be boxes.
+    ///
+    /// This is the length of [Chain::known_cells].
+    pub fn overlap(&self) -> usize {
+        self.known_cells().len()
+    }
+
     /// Checks if the exact location of the chain has been found.
     pub fn solved(&self) -> bool {
         self.end - self.start == self.len
     }
 
     /// Updates the start of a the chain.
-    pub fn update_start(&mut self, line: &Vec<PartCell<T>>, end: usize) -> Result<(), Error> {
+    pub fn update_start(&mut self, line: &[PartCell<T>], end: usize) -> Result<(), Error> {
         self.update_start_by_box_at_end(line, end);
         self.update_start_by_adjacent(line)?;
         self.update_start_by_gabs(line)
     }
 
     /// Mirror of [Chain::update_start].
-    pub fn update_end(&mut self, line: &Vec<PartCell<T>>, start: usize) -> Result<(), Error> {
+    pub fn update_end(&mut self, line: &[PartCell<T>], start: usize) -> Result<(), Error> {
         self.update_end_by_box_at_start(line, start);
         self.update_end_by_adjacent(line)?;
         self.update_end_by_gabs(line)
@@ -77,25 +85,17 @@ impl<T: Copy + PartialEq> Chain<T> {
 
     /// The smallest start value of the previous chain before we need to backtrack.
     pub fn min_prev_start(&self, same_color: bool) -> usize {
-        if same_color {
-            self.start + self.len + 1
-        } else {
-            self.start + self.len
-        }
+        self.start + self.len + min_gap(same_color)
     }
 
     /// The highest end value of the previous chain before we need to backtrack.
     pub fn max_prev_end(&self, same_color: bool) -> usize {
-        if same_color {
-            self.end - self.len - 1
-        } else {
-            self.end - self.len
-        }
+        self.end - self.len - min_gap(same_color)
     }
 
     /// Finds a more precise start by looking at boxes on the right.
     /// Boxes beyond the `end` parameter are ignored.
-    fn update_start_by_box_at_end(&mut self, line: &Vec<PartCell<T>>, end: usize) {
+    fn update_start_by_box_at_end(&mut self, line: &[PartCell<T>], end: usize) {
         let start = self.start + self.len;
 
         for i in (start..end).rev() {
@@ -107,11 +107,11 @@ impl<T: Copy + PartialEq> Chain<T> {
     }
 
     /// Mirror of [Chain::update_start_by_box_at_end].
-    fn update_end_by_box_at_start(&mut self, line: &Vec<PartCell<T>>, start: usize) {
+    fn update_end_by_box_at_start(&mut self, line: &[PartCell<T>], start: usize) {
         let end = self.end - self.len;
 
-        for i in start..end {
-            if line[i] == self.color {
+        for (i, cell) in line.iter().enumerate().take(end).skip(start) {
+            if *cell == self.color {
                 self.end = i + self.len;
                 return;
             }
@@ -120,7 +120,7 @@ impl<T: Copy + PartialEq> Chain<T> {
 
     /// Finds a more precise start by looking at adjacent same colored boxes.
     /// Fails if the range between start and end gets too small to fit the chain.
-    fn update_start_by_adjacent(&mut self, line: &Vec<PartCell<T>>) -> Result<(), Error> {
+    fn update_start_by_adjacent(&mut self, line: &[PartCell<T>]) -> Result<(), Error> {
         if self.start == 0 {
             return Ok(());
         }
@@ -132,11 +132,11 @@ impl<T: Copy + PartialEq> Chain<T> {
                 return Ok(());
             }
         }
-        Err(Error::Invalid)
+        Err(Error::invalid())
     }
 
     /// Mirror of [Chain::update_start_by_adjacent].
-    fn update_end_by_adjacent(&mut self, line: &Vec<PartCell<T>>) -> Result<(), Error> {
+    fn update_end_by_adjacent(&mut self, line: &[PartCell<T>]) -> Result<(), Error> {
         if self.end == line.len() {
             return Ok(());
         }
@@ -148,16 +148,16 @@ impl<T: Copy + PartialEq> Chain<T> {
                 return Ok(());
             }
         }
-        Err(Error::Invalid)
+        Err(Error::invalid())
     }
 
     /// Finds a more precise start by looking for a gab between spaces and other colored boxes.
     /// Fails if the range between start and end gets too small to fit the chain.
-    fn update_start_by_gabs(&mut self, line: &Vec<PartCell<T>>) -> Result<(), Error> {
+    fn update_start_by_gabs(&mut self, line: &[PartCell<T>]) -> Result<(), Error> {
         let mut count = 0;
 
-        for i in self.start..self.end {
-            count = match line[i] {
+        for (i, cell) in line.iter().enumerate().take(self.end).skip(self.start) {
+            count = match *cell {
                 PartCell::Space => 0,
                 PartCell::Box { color } if color != self.color => 0,
                 _ => count + 1,
@@ -167,11 +167,11 @@ impl<T: Copy + PartialEq> Chain<T> {
                 return Ok(());
             }
         }
-        Err(Error::Invalid)
+        Err(Error::invalid())
     }
 
     /// Mirror of [Chain::update_start_by_gabs].
-    fn update_end_by_gabs(&mut self, line: &Vec<PartCell<T>>) -> Result<(), Error> {
+    fn update_end_by_gabs(&mut self, line: &[PartCell<T>]) -> Result<(), Error> {
         let mut count = 0;
 
         for i in (self.start..self.end).rev() {
@@ -185,7 +185,7 @@ impl<T: Copy + PartialEq> Chain<T> {
                 return Ok(());
             }
         }
-        Err(Error::Invalid)
+        Err(Error::invalid())
     }
 }
 
@@ -226,6 +226,42 @@ mod test {
         assert_eq!(4..6, Chain::new((), 4, 2, 8).known_cells());
     }
 
+    #[test]
+    fn chain_overlap() {
+        assert_eq!(0, Chain::new((), 5, 0, 10).overlap());
+        assert_eq!(0, Chain::new((), 5, 0, 12).overlap());
+        assert_eq!(3, Chain::new((), 5, 2, 9).overlap());
+        assert_eq!(5, Chain::new((), 5, 3, 8).overlap());
+    }
+
+    #[test]
+    fn chain_overlap_zero() {
+        assert_eq!(0, Chain::new((), 4, 2, 10).overlap());
+    }
+
+    #[test]
+    fn chain_overlap_partial() {
+        assert_eq!(1, Chain::new((), 4, 2, 9).overlap());
+        assert_eq!(3, Chain::new((), 4, 2, 7).overlap());
+    }
+
+    #[test]
+    fn chain_overlap_solved() {
+        for len in 1..5 {
+            let chain = Chain::new((), len, 3, 3 + len);
+
+            assert!(chain.solved());
+            assert_eq!(len, chain.overlap());
+        }
+    }
+
+    #[test]
+    fn chain_overlap_matches_known_cells() {
+        let chain = Chain::new((), 4, 2, 8);
+
+        assert_eq!(chain.known_cells().count(), chain.overlap());
+    }
+
     #[test]
     fn chain_solved() {
         assert!(Chain::new((), 3, 6, 9).solved());
@@ -1,60 +1,349 @@
 use crate::algo::Error;
-use crate::{Nonogram, Solution, Status, Token};
-use std::sync::Mutex;
+use crate::{Nonogram, Solution, SolutionCount, Status, Token};
+use std::ops::DerefMut;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// The mutex guarding the found solutions.
+///
+/// `parking_lot` doesn't poison, so locking needs no unwrapping.
+#[cfg(feature = "parking_lot")]
+type Mutex<T> = parking_lot::Mutex<T>;
+
+/// The mutex guarding the found solutions.
+#[cfg(not(feature = "parking_lot"))]
+type Mutex<T> = std::sync::Mutex<T>;
+
+/// Locks a [Mutex].
+#[cfg(feature = "parking_lot")]
+fn lock<T>(mutex: &Mutex<T>) -> impl DerefMut<Target = T> + '_ {
+    mutex.lock()
+}
+
+/// Locks a [Mutex].
+///
+/// # Panics
+/// If the mutex is poisoned, which can't happen as no code panics while holding the lock.
+#[cfg(not(feature = "parking_lot"))]
+fn lock<T>(mutex: &Mutex<T>) -> impl DerefMut<Target = T> + '_ {
+    mutex.lock().unwrap()
+}
+
+/// Consumes a [Mutex], returning the inner value.
+#[cfg(feature = "parking_lot")]
+fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex.into_inner()
+}
+
+/// Consumes a [Mutex], returning the inner value.
+#[cfg(not(feature = "parking_lot"))]
+fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex.into_inner().unwrap()
+}
+
+/// A sink for the solutions found by a [crate::algo::Branch].
+pub trait Collector<T>: Sync {
+    /// Adds a nonogram to the found solutions.
+    fn push(&self, nonogram: Nonogram<T>);
+
+    /// Checks if the solving process should be aborted.
+    fn check(&self) -> Result<(), Error>;
+
+    /// Checks if a branch with the given depth should be explored.
+    fn check_depth(&self, _depth: usize) -> Result<(), Error> {
+        Ok(())
+    }
+}
 
 /// A temporary collection of the solutions found.
+///
+/// The solving process only stops due to the limit once a solution beyond the limit has been found,
+/// so the resulting [Solution] is [Status::Full] only if more solutions exist than the limit allows.
+/// The solution beyond the limit is dropped.
 pub struct Collection<TValue, TToken> {
     collection: Mutex<Vec<Nonogram<TValue>>>,
     limit: usize,
     token: TToken,
+    dedup: bool,
+    max_depth: usize,
+    depth_exceeded: AtomicBool,
+    full: AtomicBool,
 }
 
 impl<TValue: PartialEq, TToken: Token> Collection<TValue, TToken> {
     /// Creates a new collection.
     pub fn new(limit: usize, token: TToken) -> Self {
+        Self::with_dedup(false, limit, token)
+    }
+
+    /// Creates a new collection which optionally drops duplicate solutions.
+    ///
+    /// With `dedup` enabled, every push compares the nonogram against all solutions found so far,
+    /// which costs `O(n)` comparisons per push while holding the lock.
+    pub fn with_dedup(dedup: bool, limit: usize, token: TToken) -> Self {
         Self {
             collection: Mutex::new(Vec::new()),
             limit,
             token,
+            dedup,
+            max_depth: usize::MAX,
+            depth_exceeded: AtomicBool::new(false),
+            full: AtomicBool::new(false),
         }
     }
 
+    /// Skips all branches deeper than `max_depth`.
+    ///
+    /// If any branch has been skipped, the solution is [Status::DepthExceeded].
+    pub fn limit_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
     /// Adds a nonogram to the found solutions.
+    ///
+    /// If deduplication is enabled, the nonogram is dropped when it has already been found.
+    /// If the limit has already been reached, the nonogram is dropped and the collection is full.
+    pub fn push(&self, nonogram: Nonogram<TValue>) {
+        let mut collection = lock(&self.collection);
+
+        if self.dedup && collection.contains(&nonogram) {
+            return;
+        }
+        if collection.len() >= self.limit {
+            self.full.store(true, Ordering::Relaxed);
+        } else {
+            collection.push(nonogram);
+        }
+    }
+
+    /// Returns the amount of solutions found so far.
+    pub fn len(&self) -> usize {
+        lock(&self.collection).len()
+    }
+
+    /// Checks if no solution has been found so far.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the amount of solutions found has reached the limit.
+    pub fn is_limit_reached(&self) -> bool {
+        self.len() >= self.limit
+    }
+
+    /// Checks if the solving process should be aborted.
+    ///
+    /// Fails with [Error::Full] once a solution beyond the limit has been found.
+    pub fn check(&self) -> Result<(), Error> {
+        self.token.check()?;
+
+        if self.full.load(Ordering::Relaxed) {
+            Err(Error::Full)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<TValue: PartialEq + Send, TToken: Token> Collector<TValue> for Collection<TValue, TToken> {
+    fn push(&self, nonogram: Nonogram<TValue>) {
+        Collection::push(self, nonogram)
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        Collection::check(self)
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), Error> {
+        if depth > self.max_depth {
+            self.depth_exceeded.store(true, Ordering::Relaxed);
+
+            Err(Error::DepthExceeded)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A collection split into multiple shards to reduce lock contention.
+///
+/// Each rayon worker pushes into the shard selected by its thread index.
+/// Like [Collection], it is only full once a solution beyond the limit has been found.
+pub struct ShardedCollection<TValue, TToken> {
+    shards: Vec<Mutex<Vec<Nonogram<TValue>>>>,
+    limit: usize,
+    token: TToken,
+    len: AtomicUsize,
+}
+
+impl<TValue: PartialEq, TToken: Token> ShardedCollection<TValue, TToken> {
+    /// Creates a new collection with one shard per rayon worker thread.
+    pub fn new(limit: usize, token: TToken) -> Self {
+        Self::with_shards(rayon::current_num_threads(), limit, token)
+    }
+
+    /// Creates a new collection with the given amount of shards.
+    ///
+    /// # Panics
+    /// If `shards` is zero.
+    pub fn with_shards(shards: usize, limit: usize, token: TToken) -> Self {
+        assert!(shards > 0);
+
+        Self {
+            shards: (0..shards).map(|_| Mutex::new(Vec::new())).collect(),
+            limit,
+            token,
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Adds a nonogram to the shard of the current thread.
+    ///
+    /// If the limit has already been reached, the nonogram is dropped.
     pub fn push(&self, nonogram: Nonogram<TValue>) {
-        self.collection.lock().unwrap().push(nonogram);
+        if self.len.fetch_add(1, Ordering::Relaxed) >= self.limit {
+            return;
+        }
+        let thread = rayon::current_thread_index().unwrap_or(0);
+        let shard = &self.shards[thread % self.shards.len()];
+
+        lock(shard).push(nonogram);
     }
 
     /// Checks if the solving process should be aborted.
+    ///
+    /// Fails with [Error::Full] once a solution beyond the limit has been found.
     pub fn check(&self) -> Result<(), Error> {
         self.token.check()?;
 
-        if self.collection.lock().unwrap().len() >= self.limit {
+        if self.is_full() {
             Err(Error::Full)
         } else {
             Ok(())
         }
     }
+
+    /// Checks if a solution beyond the limit has been found.
+    fn is_full(&self) -> bool {
+        self.len.load(Ordering::Relaxed) > self.limit
+    }
+}
+
+impl<TValue: PartialEq + Send, TToken: Token> Collector<TValue>
+    for ShardedCollection<TValue, TToken>
+{
+    fn push(&self, nonogram: Nonogram<TValue>) {
+        ShardedCollection::push(self, nonogram)
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        ShardedCollection::check(self)
+    }
+}
+
+/// Counts the solutions found without storing them.
+///
+/// Like [Collection], it is only full once a solution beyond the limit has been found.
+pub struct Counter<TToken> {
+    count: AtomicUsize,
+    limit: usize,
+    token: TToken,
+}
+
+impl<TToken: Token> Counter<TToken> {
+    /// Creates a new counter.
+    pub fn new(limit: usize, token: TToken) -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            limit,
+            token,
+        }
+    }
+
+    /// Returns the amount of solutions found so far, at most the limit.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed).min(self.limit)
+    }
+
+    /// Checks if the solving process should be aborted.
+    ///
+    /// Fails with [Error::Full] once a solution beyond the limit has been found.
+    pub fn check(&self) -> Result<(), Error> {
+        self.token.check()?;
+
+        if self.is_full() {
+            Err(Error::Full)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks if a solution beyond the limit has been found.
+    fn is_full(&self) -> bool {
+        self.count.load(Ordering::Relaxed) > self.limit
+    }
+}
+
+impl<TValue, TToken: Token> Collector<TValue> for Counter<TToken> {
+    fn push(&self, _: Nonogram<TValue>) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        Counter::check(self)
+    }
+}
+
+/// Returns the [Status] of a finished solving process.
+fn status(check: Result<(), Error>) -> Status {
+    match check {
+        Ok(_) => Status::Complete,
+        Err(Error::Full) => Status::Full,
+        Err(Error::Cancelled) => Status::Cancelled,
+        _ => panic!(),
+    }
 }
 
 impl<T: Copy + PartialEq + Send, TToken: Token> From<Collection<T, TToken>> for Solution<T> {
     fn from(collection: Collection<T, TToken>) -> Self {
-        let status = match collection.check() {
-            Ok(_) => Status::Complete,
-            Err(Error::Full) => Status::Full,
-            Err(Error::Cancelled) => Status::Cancelled,
-            _ => panic!(),
+        let status = match status(collection.check()) {
+            Status::Complete if collection.depth_exceeded.into_inner() => Status::DepthExceeded,
+            status => status,
         };
+
+        Solution {
+            collection: into_inner(collection.collection),
+            status,
+        }
+    }
+}
+
+impl<T: Copy + PartialEq + Send, TToken: Token> From<ShardedCollection<T, TToken>> for Solution<T> {
+    fn from(collection: ShardedCollection<T, TToken>) -> Self {
+        let status = status(collection.check());
+
         Solution {
-            collection: collection.collection.into_inner().unwrap(),
+            collection: collection.shards.into_iter().flat_map(into_inner).collect(),
             status,
         }
     }
 }
 
+impl<TToken: Token> From<Counter<TToken>> for SolutionCount {
+    fn from(counter: Counter<TToken>) -> Self {
+        SolutionCount {
+            status: status(counter.check()),
+            count: counter.count(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::{Branch, PartCell};
     use crate::cancel::Cancel;
+    use crate::Item;
 
     #[test]
     fn collection_push() {
@@ -68,6 +357,63 @@ mod test {
         assert_eq!(3, solution.collection.len());
     }
 
+    #[test]
+    fn collection_len() {
+        let collection: Collection<i32, ()> = Collection::new(5, ());
+
+        assert!(collection.is_empty());
+
+        for _ in 0..3 {
+            collection.push(Nonogram::new(3, 3));
+        }
+        assert_eq!(3, collection.len());
+        assert!(!collection.is_limit_reached());
+
+        for _ in 0..2 {
+            collection.push(Nonogram::new(3, 3));
+        }
+        assert_eq!(5, collection.len());
+        assert!(collection.is_limit_reached());
+    }
+
+    #[test]
+    fn counter_push() {
+        let counter = Counter::new(usize::MAX, ());
+
+        for _ in 0..3 {
+            Collector::<()>::push(&counter, Nonogram::new(2, 2));
+        }
+        let count: SolutionCount = counter.into();
+
+        assert_eq!(3, count.count);
+        assert!(matches!(count.status, Status::Complete));
+    }
+
+    #[test]
+    fn counter_status_full() {
+        let counter = Counter::new(2, ());
+
+        for _ in 0..3 {
+            Collector::<()>::push(&counter, Nonogram::new(2, 2));
+        }
+        let count: SolutionCount = counter.into();
+
+        assert_eq!(2, count.count);
+        assert!(matches!(count.status, Status::Full));
+    }
+
+    #[test]
+    fn counter_status_limit_reached() {
+        let counter = Counter::new(2, ());
+
+        for _ in 0..2 {
+            Collector::<()>::push(&counter, Nonogram::new(2, 2));
+        }
+        let count: SolutionCount = counter.into();
+
+        assert!(matches!(count.status, Status::Complete));
+    }
+
     #[test]
     fn collection_status_complete() {
         let collection = Collection::new(usize::MAX, ());
@@ -82,15 +428,29 @@ mod test {
         collection.push(Nonogram::new(3, 3));
         collection.push(Nonogram::new(3, 3));
         collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
 
         let solution: Solution<i32> = collection.into();
 
+        assert_eq!(3, solution.len());
         assert!(matches!(solution.status, Status::Full));
     }
 
+    #[test]
+    fn collection_status_limit_reached() {
+        let collection = Collection::new(3, ());
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+
+        let solution: Solution<i32> = collection.into();
+
+        assert!(matches!(solution.status, Status::Complete));
+    }
+
     #[test]
     fn collection_status_canceled() {
-        let collection = Collection::new(3, Cancel::default());
+        let collection = Collection::new(3, Cancel);
         let solution: Solution<i32> = collection.into();
 
         assert!(matches!(solution.status, Status::Cancelled));
@@ -113,12 +473,104 @@ mod test {
         collection.push(Nonogram::new(3, 3));
         collection.push(Nonogram::new(3, 3));
 
+        assert!(matches!(collection.check(), Ok(())));
+    }
+
+    #[test]
+    fn collection_check_limit_exceeded() {
+        let collection: Collection<(), ()> = Collection::new(3, ());
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+
         assert!(matches!(collection.check(), Err(Error::Full)));
     }
 
     #[test]
     fn collection_check_cancelled() {
-        let collection: Collection<(), Cancel> = Collection::new(3, Cancel::default());
+        let collection: Collection<(), Cancel> = Collection::new(3, Cancel);
+
+        assert!(matches!(collection.check(), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn collection_push_dedup() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+
+        let plain = Collection::new(usize::MAX, ());
+        let dedup = Collection::with_dedup(true, usize::MAX, ());
+
+        // The forks of a single branch never overlap, but branches solved into the same
+        // collection do: the hinted branch finds one of the two diagonals again.
+        for collection in [&plain, &dedup] {
+            Branch::build(cols.clone(), rows.clone()).solve(collection);
+
+            let mut hinted = Branch::build(cols.clone(), rows.clone());
+            hinted.hint(0, 0, PartCell::Box { color: 'a' }).unwrap();
+            hinted.solve(collection);
+        }
+        let plain: Solution<char> = plain.into();
+        let dedup: Solution<char> = dedup.into();
+
+        assert_eq!(3, plain.collection.len());
+        assert_eq!(2, dedup.collection.len());
+    }
+
+    #[test]
+    fn sharded_collection_push() {
+        let collection = ShardedCollection::with_shards(4, usize::MAX, ());
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+
+        let solution: Solution<i32> = collection.into();
+
+        assert_eq!(3, solution.collection.len());
+    }
+
+    #[test]
+    fn sharded_collection_push_parallel() {
+        let collection = ShardedCollection::new(usize::MAX, ());
+
+        rayon::scope(|s| {
+            for _ in 0..16 {
+                s.spawn(|_| collection.push(Nonogram::new(3, 3)));
+            }
+        });
+        let solution: Solution<i32> = collection.into();
+
+        assert_eq!(16, solution.collection.len());
+    }
+
+    #[test]
+    fn sharded_collection_status_full() {
+        let collection = ShardedCollection::with_shards(2, 3, ());
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+
+        let solution: Solution<i32> = collection.into();
+
+        assert_eq!(3, solution.len());
+        assert!(matches!(solution.status, Status::Full));
+    }
+
+    #[test]
+    fn sharded_collection_check_limit_not_reached() {
+        let collection: ShardedCollection<(), ()> = ShardedCollection::with_shards(3, 5, ());
+        collection.push(Nonogram::new(3, 3));
+        collection.push(Nonogram::new(3, 3));
+
+        assert!(matches!(collection.check(), Ok(())));
+    }
+
+    #[test]
+    fn sharded_collection_check_cancelled() {
+        let collection: ShardedCollection<(), Cancel> =
+            ShardedCollection::with_shards(3, 3, Cancel);
 
         assert!(matches!(collection.check(), Err(Error::Cancelled)));
     }
@@ -1,62 +1,182 @@
 use crate::algo::line::Line;
 use crate::algo::{Error, PartCell};
-use crate::{Item, Nonogram};
+use crate::{Item, Nonogram, NonogramError};
+use std::sync::Arc;
 
 /// A group of lines including metadata.
+///
+/// Cloning a grid is cheap, because the lines are shared until one of the clones alters them.
 #[derive(Clone)]
 pub struct Grid<T> {
-    lines: Vec<Line<T>>,
+    // Forking a branch clones both grids, but the forks usually change only a few lines
+    // before they are forked again. Copying a line on its first write keeps forks cheap.
+    lines: Vec<Arc<Line<T>>>,
+    // The amount of flagged lines, unless a line has been altered through Grid::iter_lines_mut.
+    flagged_count: Option<usize>,
 }
 
 impl<T: Copy + PartialEq> Grid<T> {
     /// Constructs a new grid.
     pub fn build(numbers: Vec<Vec<Item<T>>>, length: usize) -> Self {
-        let lines = numbers
+        let lines: Vec<_> = numbers
             .into_iter()
-            .map(|col| Line::build(col, length))
+            .map(|col| Arc::new(Line::build(col, length)))
             .collect();
+        let flagged_count = lines.iter().filter(|line| line.flagged()).count();
 
-        Self { lines }
+        Self {
+            lines,
+            flagged_count: Some(flagged_count),
+        }
     }
 
     /// Returns whether the grid needs to be updated.
     pub fn flagged(&self) -> bool {
-        self.lines
-            .iter()
-            .map(Line::flagged)
-            .fold(false, |a, b| a | b)
+        match self.flagged_count {
+            Some(count) => count > 0,
+            None => self.iter_lines().any(Line::flagged),
+        }
+    }
+
+    /// Returns the amount of lines which need to be updated.
+    pub fn flagged_line_count(&self) -> usize {
+        self.flagged_count
+            .unwrap_or_else(|| self.iter_lines().filter(|line| line.flagged()).count())
     }
 
     /// Updates the metadata and writes changes.
     pub fn update(&mut self) -> Result<(), Error> {
-        for line in self.lines.iter_mut() {
-            line.update()?;
+        for (index, line) in self.lines.iter_mut().enumerate() {
+            // Updating a line which hasn't been altered since its last update changes nothing,
+            // so we don't copy it.
+            if line.flagged() {
+                Arc::make_mut(line).update().map_err(|e| e.at_line(index))?;
+
+                if let Some(count) = &mut self.flagged_count {
+                    *count -= 1;
+                }
+            }
         }
+        // Every line has been updated, so the count is known again.
+        self.flagged_count = Some(0);
+
         Ok(())
     }
 
+    /// Returns all lines.
+    ///
+    /// The lines are shared with clones of the grid through an [Arc] each,
+    /// so they aren't stored in one slice which could be borrowed as `&[Line<T>]`.
+    pub fn lines(&self) -> impl ExactSizeIterator<Item = &Line<T>> {
+        self.lines.iter().map(|line| &**line)
+    }
+
+    /// Returns all lines, like [Grid::lines].
+    pub fn iter_lines(&self) -> impl ExactSizeIterator<Item = &Line<T>> {
+        self.lines()
+    }
+
+    /// Returns all lines mutably.
+    ///
+    /// Copies every line which is still shared with a clone of the grid.
+    /// Lines altered through [Line::set] are flagged, see [Grid::flagged].
+    pub fn iter_lines_mut(&mut self) -> impl ExactSizeIterator<Item = &mut Line<T>> {
+        // The lines may be altered after the iterator is dropped,
+        // so the flagged lines are counted again by the next update.
+        self.flagged_count = None;
+        self.lines.iter_mut().map(Arc::make_mut)
+    }
+
+    /// Returns a line.
+    ///
+    /// # Panics
+    /// If the line is out of bounds.
+    pub fn line(&self, index: usize) -> &Line<T> {
+        &self.lines[index]
+    }
+
     /// Returns the value of a cell.
     pub fn get(&self, line: usize, cell: usize) -> PartCell<T> {
         self.lines[line].get(cell)
     }
 
+    /// Returns all cells line by line.
+    ///
+    /// Tuple: `(line, cell, value)`
+    pub fn cells(&self) -> impl ExactSizeIterator<Item = (usize, usize, PartCell<T>)> + '_ {
+        let (lines, cells) = self.len();
+
+        (0..lines * cells).map(move |index| {
+            let (line, cell) = (index / cells, index % cells);
+
+            (line, cell, self.get(line, cell))
+        })
+    }
+
     /// Sets the value of a cell.
     ///
     /// Flags the grid, if it has been altered.
     /// See [Grid::flagged].
     pub fn set(&mut self, line: usize, cell: usize, value: PartCell<T>) -> Result<(), Error> {
-        self.lines[line].set(cell, value)
+        if self.lines[line].get(cell) == value {
+            return Ok(());
+        }
+        let data = Arc::make_mut(&mut self.lines[line]);
+        let flagged = data.flagged();
+
+        data.set(cell, value).map_err(|e| e.at_line(line))?;
+
+        if let Some(count) = &mut self.flagged_count {
+            if !flagged && data.flagged() {
+                *count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resets a line to the state after [Grid::build], e.g. to undo a step of a solver.
+    ///
+    /// **All** values of the line are lost, including the ones which have been deduced
+    /// before the step that should be undone.
+    /// The intersecting grid is not changed, so [Grid::write_to] restores any value it still has.
+    ///
+    /// # Panics
+    /// If the line is out of bounds.
+    pub fn reset_line(&mut self, index: usize) {
+        let line = Arc::make_mut(&mut self.lines[index]);
+
+        if let Some(count) = &mut self.flagged_count {
+            if !line.flagged() {
+                *count += 1;
+            }
+        }
+        line.reset();
     }
 
     /// The length of the grid and lines.
     ///
     /// Tuple: `(lines, cells)`
     pub fn len(&self) -> (usize, usize) {
-        let inner = self.lines.first().map(Line::len).unwrap_or(0);
+        let inner = self.lines.first().map(|line| line.len()).unwrap_or(0);
 
         (self.lines.len(), inner)
     }
 
+    /// Returns the share of cells which are not [PartCell::Empty].
+    ///
+    /// A grid without any cells is considered complete.
+    pub fn completion_percentage(&self) -> f64 {
+        let (lines, cells) = self.len();
+        let total = lines * cells;
+
+        if total == 0 {
+            return 1.0;
+        }
+        let known: usize = self.iter_lines().map(Line::count_known).sum();
+
+        known as f64 / total as f64
+    }
+
     /// Copies all values to the **intersecting** grid.
     pub fn write_to(&self, other: &mut Grid<T>) -> Result<(), Error> {
         let (lines, cells) = self.len();
@@ -85,7 +205,7 @@ impl<T: Copy + PartialEq> Grid<T> {
 }
 
 impl<T: Copy + PartialEq> TryFrom<Grid<T>> for Nonogram<T> {
-    type Error = ();
+    type Error = NonogramError;
 
     fn try_from(grid: Grid<T>) -> Result<Self, Self::Error> {
         let (cols, rows) = grid.len();
@@ -116,6 +236,157 @@ mod test {
         assert!(matches!(grid.get(1, 5), PartCell::Box { color: 2 }));
     }
 
+    #[test]
+    fn grid_reset_line() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let mut grid = Grid::build(cols, 3);
+
+        grid.set(0, 1, PartCell::Box { color: 'a' }).unwrap();
+        grid.update().unwrap();
+        grid.set(1, 0, PartCell::Space).unwrap();
+        grid.update().unwrap();
+
+        assert!(!grid.flagged());
+
+        grid.reset_line(0);
+
+        assert!(grid.line(0).flagged());
+        assert_eq!(1, grid.flagged_line_count());
+        assert!((0..3).all(|cell| matches!(grid.get(0, cell), PartCell::Empty)));
+        assert!(matches!(grid.get(1, 0), PartCell::Space));
+
+        let chain = &grid.line(0).chains()[0];
+
+        assert_eq!((0, 3), (chain.start(), chain.end()));
+    }
+
+    #[test]
+    fn grid_cells() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let mut grid = Grid::build(cols, 3);
+
+        grid.set(1, 2, PartCell::Space).unwrap();
+        grid.update().unwrap();
+
+        let cells: Vec<_> = grid.cells().collect();
+        let known = grid
+            .cells()
+            .filter(|(_, _, value)| value.is_known())
+            .count();
+
+        assert_eq!(6, grid.cells().len());
+        assert!(matches!(cells[1], (0, 1, PartCell::Box { color: 'a' })));
+        assert!(matches!(cells[5], (1, 2, PartCell::Space)));
+        assert_eq!(2, known);
+        assert!((grid.completion_percentage() * 6.0 - known as f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn grid_cells_empty() {
+        let grid: Grid<char> = Grid::build(vec![Vec::new(); 3], 0);
+
+        assert_eq!(0, grid.cells().len());
+    }
+
+    #[test]
+    fn grid_set_conflict() {
+        let cols = vec![Vec::new(), Vec::new()];
+        let mut grid = Grid::build(cols, 3);
+
+        grid.set(1, 2, PartCell::Box { color: 2 }).unwrap();
+
+        assert!(matches!(
+            grid.set(1, 2, PartCell::Space),
+            Err(Error::Invalid {
+                axis: None,
+                line: Some(1),
+                cell: Some(2)
+            })
+        ));
+        assert!(matches!(grid.get(1, 2), PartCell::Box { color: 2 }));
+    }
+
+    #[test]
+    fn grid_update_conflict() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 3)]];
+        let mut grid = Grid::build(cols, 2);
+
+        let error = grid.update().unwrap_err();
+
+        assert_eq!(Some(1), error.line_index());
+        assert_eq!(None, error.cell_index());
+    }
+
+    #[test]
+    fn grid_iter_lines_mut() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let mut grid = Grid::build(cols, 3);
+        grid.update().unwrap();
+        let clone = grid.clone();
+
+        for line in grid.iter_lines_mut() {
+            line.set(2, PartCell::Space).unwrap();
+        }
+
+        assert_eq!(2, grid.flagged_line_count());
+        assert!(matches!(grid.get(1, 2), PartCell::Space));
+        assert!(matches!(clone.get(1, 2), PartCell::Empty));
+
+        grid.update().unwrap();
+        grid.reset_line(1);
+
+        assert_eq!(1, grid.flagged_line_count());
+    }
+
+    #[test]
+    fn grid_iter_lines_mut_outlives_iterator() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let mut grid = Grid::build(cols, 2);
+        grid.update().unwrap();
+
+        let lines: Vec<_> = grid.iter_lines_mut().collect();
+        for line in lines {
+            line.set(0, PartCell::Space).unwrap();
+        }
+
+        assert!(grid.flagged());
+        assert_eq!(2, grid.flagged_line_count());
+    }
+
+    #[test]
+    fn grid_clone_shares_unaltered_lines() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let grid = Grid::build(cols, 3);
+        let mut clone = grid.clone();
+
+        clone.set(0, 1, PartCell::Box { color: 'a' }).unwrap();
+        clone.set(1, 0, PartCell::Empty).unwrap();
+
+        assert!(!Arc::ptr_eq(&grid.lines[0], &clone.lines[0]));
+        assert!(Arc::ptr_eq(&grid.lines[1], &clone.lines[1]));
+        assert!(matches!(grid.get(0, 1), PartCell::Empty));
+    }
+
+    #[test]
+    fn grid_lines() {
+        let cols = vec![vec![Item::new('a', 2)], Vec::new(), vec![Item::new('b', 1)]];
+        let grid = Grid::build(cols.clone(), 6);
+
+        assert_eq!(cols.len(), grid.lines().len());
+        assert_eq!(cols.len(), grid.iter_lines().len());
+        assert_eq!(6, grid.line(0).len());
+        assert_eq!(1, grid.line(2).chains().len());
+        assert!(grid.line(1).chains().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn grid_line_oob() {
+        let grid: Grid<()> = Grid::build(vec![Vec::new()], 6);
+
+        grid.line(1);
+    }
+
     #[test]
     fn grid_flagged() {
         let cols = vec![Vec::new(), Vec::new(), Vec::new(), Vec::new()];
@@ -126,6 +397,32 @@ mod test {
         assert!(grid.flagged());
     }
 
+    #[test]
+    fn grid_flagged_line_count() {
+        let cols = vec![vec![Item::new(6, 1)], vec![Item::new(6, 1)], Vec::new()];
+        let mut grid = Grid::build(cols, 3);
+
+        assert_eq!(3, grid.flagged_line_count());
+
+        grid.update().unwrap();
+
+        assert_eq!(0, grid.flagged_line_count());
+        assert!(!grid.flagged());
+
+        grid.set(0, 0, PartCell::Box { color: 6 }).unwrap();
+        grid.set(0, 1, PartCell::Space).unwrap();
+        grid.set(1, 2, PartCell::Box { color: 6 }).unwrap();
+        grid.set(2, 0, PartCell::Space).unwrap();
+
+        assert_eq!(2, grid.flagged_line_count());
+        assert!(grid.set(2, 0, PartCell::Box { color: 6 }).is_err());
+        assert_eq!(2, grid.flagged_line_count());
+
+        grid.update().unwrap();
+
+        assert_eq!(0, grid.flagged_line_count());
+    }
+
     #[test]
     fn grid_update() {
         let cols = vec![vec![Item::new(6, 2)], vec![]];
@@ -157,6 +454,41 @@ mod test {
         assert_eq!((2, 5), grid.len())
     }
 
+    #[test]
+    fn grid_completion_percentage() {
+        let cols = vec![vec![Item::new(6, 2)], vec![]];
+        let mut grid = Grid::build(cols, 2);
+
+        assert_eq!(0.0, grid.completion_percentage());
+
+        grid.set(0, 0, PartCell::Box { color: 6 }).unwrap();
+
+        assert_eq!(0.25, grid.completion_percentage());
+
+        grid.update().unwrap();
+
+        assert_eq!(1.0, grid.completion_percentage());
+    }
+
+    #[test]
+    fn grid_completion_percentage_partial() {
+        let cols = vec![vec![Item::new(6, 2)], vec![Item::new(6, 1)]];
+        let mut grid = Grid::build(cols, 3);
+
+        grid.update().unwrap();
+
+        let percentage = grid.completion_percentage();
+
+        assert!(0.0 < percentage && percentage < 1.0);
+    }
+
+    #[test]
+    fn grid_completion_percentage_empty() {
+        let grid: Grid<()> = Grid::build(Vec::new(), 5);
+
+        assert_eq!(1.0, grid.completion_percentage());
+    }
+
     #[test]
     fn grid_write_to() {
         let cols = vec![vec![Item::new(6, 2)], vec![]];
@@ -173,6 +505,22 @@ mod test {
         assert!(matches!(rows.get(1, 1), PartCell::Space));
     }
 
+    #[test]
+    fn grid_write_to_conflict() {
+        let cols = vec![vec![Item::new(6, 2)], vec![]];
+        let rows = vec![vec![], vec![]];
+        let mut cols = Grid::build(cols, 2);
+        let mut rows = Grid::build(rows, 2);
+
+        cols.update().unwrap();
+        rows.set(1, 0, PartCell::Space).unwrap();
+
+        let error = cols.write_to(&mut rows).unwrap_err();
+
+        assert_eq!(Some(1), error.line_index());
+        assert_eq!(Some(0), error.cell_index());
+    }
+
     #[test]
     fn grid_find_unsolved_some() {
         let cols = vec![vec![Item::new(5, 1)], vec![]];
@@ -195,7 +543,7 @@ mod test {
         grid.set(1, 1, PartCell::Space).unwrap();
         grid.update().unwrap();
 
-        assert!(matches!(grid.find_unsolved(), None));
+        assert!(grid.find_unsolved().is_none());
     }
 
     #[test]
@@ -220,8 +568,8 @@ mod test {
 
         cols.update().unwrap();
 
-        let nonogram: Result<Nonogram<i32>, ()> = cols.try_into();
+        let nonogram: Result<Nonogram<i32>, _> = cols.try_into();
 
-        assert!(nonogram.is_err());
+        assert_eq!(Some(NonogramError::Unsolved), nonogram.err());
     }
 }
@@ -3,10 +3,20 @@ use crate::algo::{Error, PartCell};
 use crate::Item;
 use std::ops::Range;
 
+/// The chains of a [Line].
+///
+/// Most lines have only a few chains, which can be stored inline.
+#[cfg(feature = "smallvec_opt")]
+type Chains<T> = smallvec::SmallVec<[Chain<T>; 8]>;
+
+/// The chains of a [Line].
+#[cfg(not(feature = "smallvec_opt"))]
+type Chains<T> = Vec<Chain<T>>;
+
 /// A line of a nonogram including metadata.
 #[derive(Clone)]
 pub struct Line<T> {
-    data: Vec<Chain<T>>,
+    data: Chains<T>,
     line: Vec<PartCell<T>>,
     flagged: bool,
 }
@@ -28,6 +38,18 @@ impl<T: Copy + PartialEq> Line<T> {
         }
     }
 
+    /// Returns the chains of the line.
+    pub fn chains(&self) -> &[Chain<T>] {
+        &self.data
+    }
+
+    /// Returns the chains of the line mutably.
+    ///
+    /// Changing a chain doesn't flag the line.
+    pub fn chains_mut(&mut self) -> &mut [Chain<T>] {
+        &mut self.data
+    }
+
     /// Returns whether the line needs to be updated.
     pub fn flagged(&self) -> bool {
         self.flagged
@@ -43,11 +65,31 @@ impl<T: Copy + PartialEq> Line<T> {
         Ok(())
     }
 
+    /// Resets the line to the state after [Line::build].
+    ///
+    /// All cells become [PartCell::Empty], the chains may be placed anywhere again
+    /// and the line is flagged.
+    pub fn reset(&mut self) {
+        let len = self.len();
+
+        for chain in self.data.iter_mut() {
+            chain.set_start(0);
+            chain.set_end(len);
+        }
+        self.line = vec![PartCell::Empty; len];
+        self.flagged = true;
+    }
+
     /// Returns the value of a cell.
     pub fn get(&self, index: usize) -> PartCell<T> {
         self.line[index]
     }
 
+    /// Returns the values of all cells.
+    pub fn iter_cells(&self) -> impl ExactSizeIterator<Item = PartCell<T>> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
     /// Sets the value of a cell.
     ///
     /// Flags the line, if it has been altered.
@@ -55,9 +97,15 @@ impl<T: Copy + PartialEq> Line<T> {
     ///
     /// Only [PartCell::Empty] may be override.
     pub fn set(&mut self, cell: usize, value: PartCell<T>) -> Result<(), Error> {
-        if self.line[cell] != value {
-            if !matches!(self.line[cell], PartCell::Empty) {
-                return Err(Error::Invalid);
+        let current = self.line[cell];
+
+        if current != value {
+            if current.is_known() {
+                return Err(Error::Invalid {
+                    axis: None,
+                    line: None,
+                    cell: Some(cell),
+                });
             }
             self.line[cell] = value;
             self.flagged = true;
@@ -70,6 +118,28 @@ impl<T: Copy + PartialEq> Line<T> {
         self.line.len()
     }
 
+    /// Checks if the line has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the amount of cells whose value is unknown.
+    pub fn count_empty(&self) -> usize {
+        (0..self.len()).filter(|i| self.get(*i).is_empty()).count()
+    }
+
+    /// Returns the amount of cells whose value is known.
+    pub fn count_known(&self) -> usize {
+        self.len() - self.count_empty()
+    }
+
+    /// Checks if the value of every cell is known.
+    ///
+    /// Unlike [Chain::solved], this doesn't depend on the chain ranges.
+    pub fn is_fully_determined(&self) -> bool {
+        (0..self.len()).all(|i| self.get(i).is_known())
+    }
+
     /// Searches an unsolved chain and returns a free cell with the color of the chain.
     ///
     /// Tuple: `(cell, color)`
@@ -81,6 +151,27 @@ impl<T: Copy + PartialEq> Line<T> {
             .next()
     }
 
+    /// Returns all cells which are forced to a value by the current chain ranges.
+    ///
+    /// The ranges are only narrowed down by [Line::update].
+    ///
+    /// Tuple: `(cell, value)`
+    pub fn find_all_forced(&self) -> Vec<(usize, PartCell<T>)> {
+        let mut forced = Vec::new();
+        let mut prev_end = 0;
+
+        for chain in self.data.iter() {
+            let color = chain.color();
+
+            forced.extend((prev_end..chain.start()).map(|i| (i, PartCell::Space)));
+            forced.extend(chain.known_cells().map(|i| (i, PartCell::Box { color })));
+
+            prev_end = chain.end();
+        }
+        forced.extend((prev_end..self.len()).map(|i| (i, PartCell::Space)));
+        forced
+    }
+
     /// Updates the range start of all chains.
     fn update_starts(&mut self) -> Result<(), Error> {
         // To avoid an integer overflow at minus one, we iterate with an index offset by plus one.
@@ -222,6 +313,17 @@ mod test {
     use crate::algo::PartCell::*;
     use crate::Item;
 
+    #[test]
+    fn line_iter_cells() {
+        let mut line = Line::build(vec![Item::new(3, 1)], 3);
+        line.set(0, Space).unwrap();
+        line.set(1, Box { color: 3 }).unwrap();
+
+        let cells: Vec<_> = line.iter_cells().collect();
+
+        assert_eq!(vec![Space, Box { color: 3 }, Empty], cells);
+    }
+
     #[test]
     fn line_flagged_true_on_creation() {
         let line: Line<()> = Line::build(Vec::new(), 0);
@@ -229,6 +331,114 @@ mod test {
         assert!(line.flagged());
     }
 
+    #[test]
+    fn line_set_conflict() {
+        let mut line = Line::build(vec![Item::new('a', 1)], 3);
+        line.set(2, Space).unwrap();
+
+        let error = line.set(2, Box { color: 'a' }).unwrap_err();
+
+        assert_eq!(None, error.line_index());
+        assert_eq!(Some(2), error.cell_index());
+    }
+
+    #[test]
+    fn line_update_conflict() {
+        let mut line = Line::build(vec![Item::new('a', 2)], 3);
+        line.set(1, Space).unwrap();
+
+        assert!(matches!(
+            line.update(),
+            Err(Error::Invalid {
+                axis: None,
+                line: None,
+                cell: None
+            })
+        ));
+    }
+
+    #[test]
+    fn line_count_new() {
+        let line = Line::build(vec![Item::new('a', 2)], 5);
+
+        assert_eq!(5, line.count_empty());
+        assert_eq!(0, line.count_known());
+    }
+
+    #[test]
+    fn line_count_partial() {
+        let mut line = Line::build(vec![Item::new('a', 3)], 5);
+        line.update().unwrap();
+
+        assert_eq!(4, line.count_empty());
+        assert_eq!(1, line.count_known());
+    }
+
+    #[test]
+    fn line_count_determined() {
+        let mut line = Line::build(vec![Item::new('a', 2), Item::new('a', 2)], 5);
+        line.update().unwrap();
+
+        assert_eq!(0, line.count_empty());
+        assert_eq!(5, line.count_known());
+    }
+
+    #[test]
+    fn line_is_fully_determined() {
+        let data = vec![Item::new(1_u8, 2), Item::new(1, 2)];
+        let mut line = Line::build(data, 5);
+        line.update().unwrap();
+
+        assert!(line.is_fully_determined());
+    }
+
+    #[test]
+    fn line_is_fully_determined_unknown() {
+        let data = vec![Item::new(1_u8, 2), Item::new(1, 1)];
+        let mut line = Line::build(data, 5);
+        line.update().unwrap();
+
+        assert!(matches!(line.get(4), Empty));
+        assert!(!line.is_fully_determined());
+    }
+
+    #[test]
+    fn line_is_fully_determined_empty() {
+        let line: Line<()> = Line::build(Vec::new(), 0);
+
+        assert!(line.is_fully_determined());
+    }
+
+    #[test]
+    fn line_chains() {
+        let line = Line::build(vec![Item::new('a', 3)], 7);
+
+        assert_eq!(1, line.chains().len());
+        assert_eq!(0, line.chains()[0].start());
+        assert_eq!(7, line.chains()[0].end());
+    }
+
+    #[test]
+    fn line_chains_skips_empty_items() {
+        let line = Line::build(vec![Item::new('a', 0), Item::new('b', 2)], 7);
+
+        assert_eq!(1, line.chains().len());
+        assert!(line.chains()[0].color() == 'b');
+    }
+
+    #[test]
+    fn line_chains_mut() {
+        let mut line = Line::build(vec![Item::new('a', 3)], 7);
+        line.chains_mut()[0].set_start(2);
+        line.chains_mut()[0].set_end(5);
+        line.update().unwrap();
+
+        assert!(line.chains()[0].solved());
+        assert!(matches!(line.get(1), Space));
+        assert!(matches!(line.get(2), Box { color: 'a' }));
+        assert!(matches!(line.get(5), Space));
+    }
+
     #[test]
     fn line_len() {
         assert_eq!(5, Line::<()>::build(Vec::new(), 5).len());
@@ -407,7 +617,7 @@ mod test {
 
         line.update().unwrap();
 
-        assert!(matches!(line.find_unsolved(), None));
+        assert!(line.find_unsolved().is_none());
     }
 
     #[test]
@@ -422,6 +632,47 @@ mod test {
         assert!(matches!(line.find_unsolved(), Some((1, 'a'))));
     }
 
+    #[test]
+    fn line_find_all_forced_determined() {
+        let data = vec![Item::new('a', 2), Item::new('a', 1)];
+        let mut line = Line::build(data, 4);
+        line.update().unwrap();
+
+        let forced = line.find_all_forced();
+
+        assert_eq!(
+            vec![
+                (0, Box { color: 'a' }),
+                (1, Box { color: 'a' }),
+                (2, Space),
+                (3, Box { color: 'a' }),
+            ],
+            forced
+        );
+    }
+
+    #[test]
+    fn line_find_all_forced_ambiguous() {
+        let data = vec![Item::new('a', 1)];
+        let mut line = Line::build(data, 3);
+        line.update().unwrap();
+
+        assert!(line.find_all_forced().is_empty());
+    }
+
+    #[test]
+    fn line_find_all_forced_partial() {
+        let data = vec![Item::new('a', 3)];
+        let mut line = Line::build(data, 5);
+        line.set(4, Space).unwrap();
+        line.update().unwrap();
+
+        assert_eq!(
+            vec![(1, Box { color: 'a' }), (2, Box { color: 'a' }), (4, Space)],
+            line.find_all_forced()
+        );
+    }
+
     #[test]
     fn line_new_zeros() {
         let data = vec![
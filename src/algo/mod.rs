@@ -1,6 +1,7 @@
-use crate::{Cancelled, Cell, Item, Token};
-use collection::Collection;
+use crate::{Cancelled, Cell, Item, NonogramError, Token};
+use collection::Collector;
 use grid::Grid;
+use line::Line;
 use rayon::join;
 
 pub mod chain;
@@ -19,15 +20,122 @@ pub enum PartCell<T> {
     Space,
 }
 
+impl<T> PartCell<T> {
+    /// Checks if the value of the cell is known.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, PartCell::Empty)
+    }
+
+    /// Checks if the value of the cell is unknown.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, PartCell::Empty)
+    }
+
+    /// Checks if the cell is a box.
+    pub fn is_box(&self) -> bool {
+        matches!(self, PartCell::Box { .. })
+    }
+
+    /// Checks if the cell is a space.
+    pub fn is_space(&self) -> bool {
+        matches!(self, PartCell::Space)
+    }
+}
+
+/// The direction of a line.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Axis {
+    /// A column, going from top to bottom.
+    Col,
+    /// A row, going from left to right.
+    Row,
+}
+
 /// The reason a nonogram could not be solved.
 #[derive(Debug)]
 pub enum Error {
     /// The supplied data doesn't result in a valid nonogram.
-    Invalid,
+    ///
+    /// Contains whether the conflicting line is a column or a row, the index of the line
+    /// and the index of the cell within the line, if they are known.
+    Invalid {
+        axis: Option<Axis>,
+        line: Option<usize>,
+        cell: Option<usize>,
+    },
     /// The collection was full.
     Full,
     /// The operation has been cancelled.
     Cancelled,
+    /// The branch requires more nested guesses than allowed.
+    DepthExceeded,
+}
+
+impl Error {
+    /// Constructs an [Error::Invalid] without a known position.
+    pub(crate) fn invalid() -> Self {
+        Error::Invalid {
+            axis: None,
+            line: None,
+            cell: None,
+        }
+    }
+
+    /// Returns whether the conflicting line is a column or a row, if known.
+    pub fn axis(&self) -> Option<Axis> {
+        match self {
+            Error::Invalid { axis, .. } => *axis,
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the conflicting line, if known.
+    pub fn line_index(&self) -> Option<usize> {
+        match self {
+            Error::Invalid { line, .. } => *line,
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the conflicting cell within its line, if known.
+    pub fn cell_index(&self) -> Option<usize> {
+        match self {
+            Error::Invalid { cell, .. } => *cell,
+            _ => None,
+        }
+    }
+
+    /// Sets the line of a conflict, unless it is already known.
+    pub(crate) fn at_line(self, index: usize) -> Self {
+        match self {
+            Error::Invalid {
+                axis,
+                line: None,
+                cell,
+            } => Error::Invalid {
+                axis,
+                line: Some(index),
+                cell,
+            },
+            error => error,
+        }
+    }
+
+    /// Sets the axis of a conflict, unless it is already known.
+    pub(crate) fn on_axis(self, axis: Axis) -> Self {
+        match self {
+            Error::Invalid {
+                axis: None,
+                line,
+                cell,
+            } => Error::Invalid {
+                axis: Some(axis),
+                line,
+                cell,
+            },
+            error => error,
+        }
+    }
 }
 
 impl From<Cancelled> for Error {
@@ -46,25 +154,35 @@ impl<T: PartialEq> PartialEq<T> for PartCell<T> {
 }
 
 impl<T> TryFrom<PartCell<T>> for Cell<T> {
-    type Error = ();
+    type Error = NonogramError;
 
     fn try_from(value: PartCell<T>) -> Result<Self, Self::Error> {
         match value {
-            PartCell::Empty => Err(()),
+            PartCell::Empty => Err(NonogramError::Unsolved),
             PartCell::Box { color } => Ok(Cell::Box { color }),
             PartCell::Space => Ok(Cell::Space),
         }
     }
 }
 
+impl<T> From<Cell<T>> for PartCell<T> {
+    fn from(value: Cell<T>) -> Self {
+        match value {
+            Cell::Box { color } => PartCell::Box { color },
+            Cell::Space => PartCell::Space,
+        }
+    }
+}
+
 /// A branch which might result in a complete nonogram.
 #[derive(Clone)]
 pub struct Branch<T> {
     cols: Grid<T>,
     rows: Grid<T>,
+    depth: usize,
 }
 
-impl<T: Copy + PartialEq + Send> Branch<T> {
+impl<T: Copy + PartialEq + Send + Sync> Branch<T> {
     /// Constructs a new branch from a layout.
     pub fn build(col_grid: Vec<Vec<Item<T>>>, row_grid: Vec<Vec<Item<T>>>) -> Self {
         let col_count = col_grid.len();
@@ -73,36 +191,83 @@ impl<T: Copy + PartialEq + Send> Branch<T> {
         let cols = Grid::build(col_grid, row_count);
         let rows = Grid::build(row_grid, col_count);
 
-        Self { cols, rows }
+        Self {
+            cols,
+            rows,
+            depth: 0,
+        }
+    }
+
+    /// Returns the amount of forks which lead to this branch.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the amount of cells whose value is known, e.g. to report progress.
+    ///
+    /// Iterates over all cells, so it shouldn't be called after every step.
+    pub fn explored_cells(&self) -> usize {
+        // The rows contain the same values.
+        self.cols.iter_lines().map(Line::count_known).sum()
+    }
+
+    /// Sets a cell before solving, e.g. from a cell placed by a player.
+    ///
+    /// Fails if the cell is already known to have a different value.
+    pub fn hint(&mut self, col: usize, row: usize, value: PartCell<T>) -> Result<(), Error> {
+        self.cols
+            .set(col, row, value)
+            .map_err(|e| e.on_axis(Axis::Col))?;
+        self.rows
+            .set(row, col, value)
+            .map_err(|e| e.on_axis(Axis::Row))
     }
 
     /// Tries to find the solution to this branch.
     /// Fails if the layout is invalid.
-    pub fn solve<TToken: Token>(mut self, collection: &Collection<T, TToken>) {
-        match self.try_solve(collection) {
-            Ok(_) => match self.find_unsolved() {
+    pub fn solve<C: Collector<T>>(mut self, collection: &C) {
+        if self.try_solve(|| collection.check()).is_ok() {
+            match self.find_unsolved() {
                 None => {
                     collection.push(self.cols.try_into().unwrap());
                 }
                 Some(unsolved) => {
-                    let (a, b) = self.fork(unsolved);
+                    if collection.check_depth(self.depth + 1).is_ok() {
+                        let (a, b) = self.fork(unsolved);
 
-                    join(|| a.solve(collection), || b.solve(collection));
+                        join(|| a.solve(collection), || b.solve(collection));
+                    }
                 }
-            },
-            Err(_) => (),
+            }
         }
     }
 
+    /// Deduces as many cells as possible without forking.
+    ///
+    /// Returns the cells row by row.
+    pub fn propagate(mut self, token: &impl Token) -> Result<Vec<Vec<PartCell<T>>>, Error> {
+        self.try_solve(|| Ok(token.check()?))?;
+
+        let (cols, rows) = self.cols.len();
+
+        Ok((0..rows)
+            .map(|row| (0..cols).map(|col| self.cols.get(col, row)).collect())
+            .collect())
+    }
+
     /// Tries to solve a branch without forking.
-    fn try_solve<TToken: Token>(&mut self, token: &Collection<T, TToken>) -> Result<(), Error> {
+    fn try_solve(&mut self, check: impl Fn() -> Result<(), Error>) -> Result<(), Error> {
         while self.cols.flagged() || self.rows.flagged() {
-            self.cols.update()?;
-            self.cols.write_to(&mut self.rows)?;
-            self.rows.update()?;
-            self.rows.write_to(&mut self.cols)?;
+            // Errors of write_to refer to the written grid.
+            let col = |e: Error| e.on_axis(Axis::Col);
+            let row = |e: Error| e.on_axis(Axis::Row);
+
+            self.cols.update().map_err(col)?;
+            self.cols.write_to(&mut self.rows).map_err(row)?;
+            self.rows.update().map_err(row)?;
+            self.rows.write_to(&mut self.cols).map_err(col)?;
 
-            token.check()?;
+            check()?;
         }
         Ok(())
     }
@@ -110,6 +275,8 @@ impl<T: Copy + PartialEq + Send> Branch<T> {
     /// Forks the branch at the given position
     /// with the given color into one with a box and one with a space.
     fn fork(mut self, (col, row, color): (usize, usize, T)) -> (Self, Self) {
+        self.depth += 1;
+
         let mut fork = self.clone();
 
         self.cols.set(col, row, PartCell::Box { color }).unwrap();
@@ -139,10 +306,54 @@ impl<T: Copy + PartialEq + Send> Branch<T> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::collection::Collection;
     use crate::cancel::Cancel;
     use crate::Cell::*;
     use crate::{Solution, Status};
 
+    #[test]
+    fn part_cell_empty() {
+        let cell: PartCell<()> = PartCell::Empty;
+
+        assert!(!cell.is_known());
+        assert!(cell.is_empty());
+        assert!(!cell.is_box());
+        assert!(!cell.is_space());
+    }
+
+    #[test]
+    fn part_cell_box() {
+        let cell = PartCell::Box { color: 3 };
+
+        assert!(cell.is_known());
+        assert!(!cell.is_empty());
+        assert!(cell.is_box());
+        assert!(!cell.is_space());
+    }
+
+    #[test]
+    fn part_cell_space() {
+        let cell: PartCell<()> = PartCell::Space;
+
+        assert!(cell.is_known());
+        assert!(!cell.is_empty());
+        assert!(!cell.is_box());
+        assert!(cell.is_space());
+    }
+
+    #[test]
+    fn branch_explored_cells() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let mut branch = Branch::build(cols, rows);
+
+        assert_eq!(0, branch.explored_cells());
+
+        branch.try_solve(|| Ok(())).unwrap();
+
+        assert_eq!(4, branch.explored_cells());
+    }
+
     #[test]
     fn branch_solve() {
         let cols = vec![
@@ -155,9 +366,9 @@ mod test {
             vec![Item::new('b', 3)],
             vec![Item::new('b', 1)],
         ];
-        let mut collection = Collection::new(usize::MAX, ());
+        let collection = Collection::new(usize::MAX, ());
 
-        Branch::build(cols, rows).solve(&mut collection);
+        Branch::build(cols, rows).solve(&collection);
 
         let solution: Solution<char> = collection.into();
         let nonogram = solution.collection.first().unwrap();
@@ -180,23 +391,54 @@ mod test {
         let cols = vec![vec![Item { color: 'a', len: 1 }]];
         let rows = vec![vec![Item { color: 'b', len: 1 }]];
 
-        let mut collection = Collection::new(usize::MAX, ());
+        let collection = Collection::new(usize::MAX, ());
 
-        Branch::build(cols, rows).solve(&mut collection);
+        Branch::build(cols, rows).solve(&collection);
 
         let solution: Solution<char> = collection.into();
 
         assert!(solution.collection.is_empty());
     }
 
+    #[test]
+    fn branch_propagate_invalid_position() {
+        let cols = vec![vec![Item { color: 'b', len: 1 }]; 2];
+        let rows = vec![
+            vec![Item { color: 'b', len: 1 }],
+            vec![Item { color: 'b', len: 2 }],
+        ];
+
+        let error = Branch::build(cols, rows).propagate(&()).unwrap_err();
+
+        assert!(error.line_index().is_some());
+    }
+
+    #[test]
+    fn branch_propagate_invalid_axis() {
+        let cols = vec![vec![Item::new('b', 1)]; 2];
+        let rows = vec![vec![Item::new('b', 3)]];
+
+        let error = Branch::build(cols.clone(), rows.clone())
+            .propagate(&())
+            .unwrap_err();
+
+        assert_eq!(Some(Axis::Row), error.axis());
+        assert_eq!(Some(0), error.line_index());
+
+        let error = Branch::build(rows, cols).propagate(&()).unwrap_err();
+
+        assert_eq!(Some(Axis::Col), error.axis());
+        assert_eq!(Some(0), error.line_index());
+    }
+
     #[test]
     fn branch_solve_invalid_empty_cols() {
         let cols = vec![];
         let rows = vec![vec![Item { color: 'b', len: 1 }]];
 
-        let mut collection = Collection::new(usize::MAX, ());
+        let collection = Collection::new(usize::MAX, ());
 
-        Branch::build(cols, rows).solve(&mut collection);
+        Branch::build(cols, rows).solve(&collection);
 
         let solution: Solution<char> = collection.into();
 
@@ -208,9 +450,9 @@ mod test {
         let cols = vec![vec![Item { color: 'b', len: 1 }]];
         let rows = vec![];
 
-        let mut collection = Collection::new(usize::MAX, ());
+        let collection = Collection::new(usize::MAX, ());
 
-        Branch::build(cols, rows).solve(&mut collection);
+        Branch::build(cols, rows).solve(&collection);
 
         let solution: Solution<char> = collection.into();
 
@@ -222,9 +464,9 @@ mod test {
         let cols = vec![];
         let rows = vec![];
 
-        let mut collection = Collection::new(usize::MAX, ());
+        let collection = Collection::new(usize::MAX, ());
 
-        Branch::build(cols, rows).solve(&mut collection);
+        Branch::build(cols, rows).solve(&collection);
 
         let solution: Solution<char> = collection.into();
 
@@ -240,15 +482,56 @@ mod test {
             vec![Item::new('a', 1)],
             vec![Item::new('a', 1)],
         ];
-        let mut collection = Collection::new(usize::MAX, ());
+        let collection = Collection::new(usize::MAX, ());
 
-        Branch::build(cols.clone(), cols).solve(&mut collection);
+        Branch::build(cols.clone(), cols).solve(&collection);
 
         let solution: Solution<char> = collection.into();
 
         assert!(!solution.collection.is_empty());
     }
 
+    #[test]
+    fn branch_depth() {
+        let data = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let branch = Branch::build(data.clone(), data);
+
+        assert_eq!(0, branch.depth());
+
+        let (a, b) = branch.fork((0, 0, 'a'));
+        let (c, _) = a.fork((1, 1, 'a'));
+
+        assert_eq!(1, b.depth());
+        assert_eq!(2, c.depth());
+    }
+
+    #[test]
+    fn branch_solve_max_depth_logical() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let collection = Collection::new(usize::MAX, ()).limit_depth(0);
+
+        Branch::build(cols, rows).solve(&collection);
+
+        let solution: Solution<char> = collection.into();
+
+        assert_eq!(1, solution.collection.len());
+        assert!(matches!(solution.status, Status::Complete));
+    }
+
+    #[test]
+    fn branch_solve_max_depth_exceeded() {
+        let data = vec![vec![Item::new('a', 1)]; 5];
+        let collection = Collection::new(usize::MAX, ()).limit_depth(0);
+
+        Branch::build(data.clone(), data).solve(&collection);
+
+        let solution: Solution<char> = collection.into();
+
+        assert!(solution.collection.is_empty());
+        assert!(matches!(solution.status, Status::DepthExceeded));
+    }
+
     #[test]
     fn branch_solve_cancel() {
         let data = vec![
@@ -258,9 +541,9 @@ mod test {
             vec![Item::new('a', 1)],
             vec![Item::new('a', 1)],
         ];
-        let mut collection = Collection::new(usize::MAX, Cancel::default());
+        let collection = Collection::new(usize::MAX, Cancel);
 
-        Branch::build(data.clone(), data).solve(&mut collection);
+        Branch::build(data.clone(), data).solve(&collection);
 
         let solution: Solution<char> = collection.into();
 
@@ -1,10 +1,16 @@
 use nonogram_rs::*;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::fs::File;
 use std::io::{stdin, stdout, Write};
+use std::ops::Range;
+use std::process::exit;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use crossterm::style::{Color, Print, SetForegroundColor};
 use crossterm::{ExecutableCommand, QueueableCommand};
+use indicatif::ProgressBar;
+use serde::Deserialize;
 
 #[derive(Parser)]
 #[command(version)]
@@ -17,13 +23,110 @@ struct Args {
 #[derive(Subcommand)]
 enum Command {
     /// Solve a nonogram from stdin
-    Solve,
+    Solve {
+        /// Sort the solutions to get the same order on every run
+        #[arg(long)]
+        sorted: bool,
+        /// Skip branches which require more nested guesses
+        #[arg(long)]
+        max_depth: Option<usize>,
+    },
+    /// Count the solutions of a nonogram from stdin
+    ///
+    /// Exits with 1 if there are more solutions than the limit and 2 if counting has been cancelled.
+    Count {
+        /// Stop counting after this many solutions
+        #[arg(long, default_value_t = usize::MAX)]
+        limit: usize,
+        /// Print the count and status as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check a nonogram from stdin for errors
+    ///
+    /// Exits with 1 if the nonogram is invalid and 2 if it can't be parsed.
+    Validate,
+    /// Swap the columns and rows of a nonogram from stdin
+    Transpose {
+        /// Solve and print the transposed nonogram
+        #[arg(long)]
+        solve: bool,
+        /// Map colors to hex codes, e.g. {"R": "#FF0000"}
+        #[arg(long, value_parser = parse_palette)]
+        palette: Option<Palette>,
+    },
     /// Print all found nonograms
-    Show,
+    Show {
+        /// Only print the nonogram at this index
+        #[arg(long, conflicts_with_all = ["range", "count"])]
+        index: Option<usize>,
+        /// Only print the nonograms in this range (e.g. "1..3")
+        #[arg(long, value_parser = parse_range, conflicts_with = "count")]
+        range: Option<Range<usize>>,
+        /// Only print the amount of nonograms
+        #[arg(long)]
+        count: bool,
+        /// The output format
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Print the clues next to the nonograms, reading {"layout": ..., "solutions": [...]}
+        #[arg(long, conflicts_with = "format")]
+        grid: bool,
+        /// Map colors to hex codes, e.g. {"R": "#FF0000"}
+        #[arg(long, value_parser = parse_palette)]
+        palette: Option<Palette>,
+    },
+    /// Generate random nonograms
+    Generate {
+        /// The amount of nonograms
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// The column count
+        #[arg(long)]
+        cols: usize,
+        /// The row count
+        #[arg(long)]
+        rows: usize,
+        /// Only include nonograms with exactly one solution
+        #[arg(long)]
+        unique: bool,
+        /// The maximum amount of generated nonograms [default: count * 100]
+        #[arg(long)]
+        max_attempts: Option<usize>,
+        /// The output format
+        #[arg(long, value_enum, default_value_t = LayoutFormat::Json)]
+        format: LayoutFormat,
+    },
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Format {
+    /// Colored blocks in the terminal
+    Text,
+    /// ASCII portable bitmaps, written to "solution_N.pbm" if there are multiple nonograms
+    Pbm,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum LayoutFormat {
+    /// A JSON array of layouts
+    Json,
+}
+
+/// Terminal colors to use instead of the built-in ones.
+type Palette = HashMap<char, Color>;
+
+/// The input of `show --grid`.
+#[derive(Deserialize)]
+struct GridInput {
+    layout: Layout<char>,
+    solutions: Vec<Nonogram<char>>,
 }
 
 enum CliError {
-    InvalidColor { color: char },
+    InvalidColor { color: String },
+    OutOfBounds { range: Range<usize>, len: usize },
+    AttemptsExceeded { attempts: usize, found: usize },
     ParsingError { error: serde_json::Error },
     IoError { error: std::io::Error },
 }
@@ -44,6 +147,16 @@ impl Debug for CliError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             CliError::InvalidColor { color } => write!(f, "invalid color: \"{}\"", color),
+            CliError::OutOfBounds { range, len } => write!(
+                f,
+                "out of bounds: {}..{} (found {} nonograms)",
+                range.start, range.end, len
+            ),
+            CliError::AttemptsExceeded { attempts, found } => write!(
+                f,
+                "gave up after {} attempts (found {} nonograms)",
+                attempts, found
+            ),
             CliError::ParsingError { error } => write!(f, "{}", error),
             CliError::IoError { error } => write!(f, "{}", error),
         }
@@ -54,70 +167,356 @@ fn main() -> Result<(), CliError> {
     let args = Args::parse();
 
     match args.command {
-        Command::Solve => solve(),
-        Command::Show => show(),
+        Command::Solve { sorted, max_depth } => solve(sorted, max_depth),
+        Command::Count { limit, json } => count(limit, json),
+        Command::Validate => validate(),
+        Command::Transpose { solve, palette } => transpose(solve, palette.unwrap_or_default()),
+        Command::Show {
+            index,
+            range,
+            count,
+            format,
+            grid,
+            palette,
+        } => show(
+            index.map(|i| i..i.saturating_add(1)).or(range),
+            count,
+            format,
+            grid,
+            palette.unwrap_or_default(),
+        ),
+        Command::Generate {
+            count,
+            cols,
+            rows,
+            unique,
+            max_attempts,
+            format,
+        } => generate(count, cols, rows, unique, max_attempts, format),
     }
 }
 
-fn solve() -> Result<(), CliError> {
+fn solve(sorted: bool, max_depth: Option<usize>) -> Result<(), CliError> {
     let layout: Layout<char> = serde_json::from_reader(stdin())?;
-    let collection = layout.solve(usize::MAX, ()).collection;
+    let mut solution = layout.solve_with_max_depth(usize::MAX, max_depth.unwrap_or(usize::MAX), ());
 
-    serde_json::to_writer(stdout(), &collection)?;
+    if sorted {
+        solution.sort();
+    }
+    serde_json::to_writer(stdout(), &solution.collection)?;
 
     stdout().execute(Print("\n"))?;
 
+    if matches!(solution.status, Status::DepthExceeded) {
+        eprintln!("Some branches exceeded the maximum depth, the solutions may be incomplete.");
+    }
     Ok(())
 }
 
-fn show() -> Result<(), CliError> {
-    let collection: Vec<Nonogram<char>> = serde_json::from_reader(stdin())?;
+fn count(limit: usize, json: bool) -> Result<(), CliError> {
+    let layout: Layout<char> = serde_json::from_reader(stdin())?;
+    let count = layout.count_solutions(limit, ());
 
-    for nonogram in collection {
-        print_nonogram(nonogram)?;
+    if json {
+        serde_json::to_writer(stdout(), &count)?;
+    } else {
+        stdout().execute(Print(count.count))?;
     }
-    stdout().flush()?;
+    stdout().execute(Print("\n"))?;
 
+    match count.status {
+        Status::Complete => Ok(()),
+        Status::Full => exit(1),
+        Status::Cancelled | Status::DepthExceeded => exit(2),
+    }
+}
+
+fn validate() -> Result<(), CliError> {
+    let layout: Layout<char> = match serde_json::from_reader(stdin()) {
+        Ok(layout) => layout,
+        Err(error) => {
+            eprintln!("{}", error);
+            exit(2);
+        }
+    };
+    if let Err(errors) = layout.validate() {
+        for error in errors {
+            eprintln!("{:?}", error);
+        }
+        exit(1);
+    }
     Ok(())
 }
 
-fn print_nonogram(nonogram: Nonogram<char>) -> Result<(), CliError> {
-    let width = nonogram.cols() * 2;
-    let meta_width = width.saturating_sub(9);
+fn transpose(solve: bool, palette: Palette) -> Result<(), CliError> {
+    let layout: Layout<char> = serde_json::from_reader(stdin())?;
+    let layout = layout.transpose();
 
-    let cols = format!("Columns: {:>meta_width$}\n", nonogram.cols());
-    let rows = format!("Rows:    {:>meta_width$}\n", nonogram.cols());
+    if solve {
+        let collection = layout.solve(usize::MAX, ()).collection;
+        let total = collection.len();
 
-    stdout()
-        .queue(Print("=".repeat(width)))?
-        .queue(Print("\n"))?
-        .queue(Print(cols))?
-        .queue(Print(rows))?
-        .queue(Print("-".repeat(width)))?
-        .queue(Print("\n"))?;
+        for (index, nonogram) in collection.into_iter().enumerate() {
+            print_nonogram(nonogram, index, total, &palette)?;
+        }
+        stdout().flush()?;
+    } else {
+        serde_json::to_writer(stdout(), &layout)?;
 
-    for row in 0..nonogram.rows() {
-        for col in 0..nonogram.cols() {
-            match nonogram[(col, row)] {
-                Cell::Box { color } => {
-                    let c = map_color(color)?;
+        stdout().execute(Print("\n"))?;
+    }
+    Ok(())
+}
 
-                    stdout().queue(SetForegroundColor(c))?;
-                    stdout().queue(Print("██"))?;
-                }
-                Cell::Space => {
-                    stdout().queue(Print("  "))?;
+fn show(
+    range: Option<Range<usize>>,
+    count: bool,
+    format: Format,
+    grid: bool,
+    palette: Palette,
+) -> Result<(), CliError> {
+    let (layout, mut collection) = if grid {
+        let input: GridInput = serde_json::from_reader(stdin())?;
+
+        (Some(input.layout), input.solutions)
+    } else {
+        (None, serde_json::from_reader(stdin())?)
+    };
+
+    if count {
+        stdout().execute(Print(format!("{}\n", collection.len())))?;
+
+        return Ok(());
+    }
+    let total = collection.len();
+    let mut offset = 0;
+
+    if let Some(range) = range {
+        if range.start > range.end || range.end > collection.len() {
+            let len = collection.len();
+
+            return Err(CliError::OutOfBounds { range, len });
+        }
+        offset = range.start;
+        collection = collection.drain(range).collect();
+    }
+    match format {
+        Format::Text => {
+            for (index, nonogram) in collection.into_iter().enumerate() {
+                let index = offset + index;
+
+                match &layout {
+                    Some(layout) => print_grid(layout, nonogram, index, total, &palette)?,
+                    None => print_nonogram(nonogram, index, total, &palette)?,
                 }
             }
         }
+        Format::Pbm if collection.len() == 1 => {
+            write_pbm(&collection[0], &mut stdout())?;
+        }
+        Format::Pbm => {
+            for (index, nonogram) in collection.iter().enumerate() {
+                let mut file = File::create(format!("solution_{}.pbm", offset + index))?;
+
+                write_pbm(nonogram, &mut file)?;
+            }
+        }
+    }
+    stdout().flush()?;
+
+    Ok(())
+}
+
+fn generate(
+    count: usize,
+    cols: usize,
+    rows: usize,
+    unique: bool,
+    max_attempts: Option<usize>,
+    format: LayoutFormat,
+) -> Result<(), CliError> {
+    let max_attempts = max_attempts.unwrap_or(count.saturating_mul(100));
+    let progress = ProgressBar::new(count as u64);
+    let mut rng = rand::thread_rng();
+    let mut layouts: Vec<Layout<char>> = Vec::with_capacity(count);
+    let mut attempts = 0;
+
+    while layouts.len() < count {
+        if attempts == max_attempts {
+            progress.abandon();
+
+            return Err(CliError::AttemptsExceeded {
+                attempts,
+                found: layouts.len(),
+            });
+        }
+        attempts += 1;
+
+        let layout = gen::random(cols, rows, &['!'], &mut rng);
+
+        if !unique || layout.clone().solve_unique(()).is_ok() {
+            layouts.push(layout);
+            progress.inc(1);
+        }
+    }
+    progress.finish_and_clear();
+
+    match format {
+        LayoutFormat::Json => serde_json::to_writer(stdout(), &layouts)?,
+    }
+    stdout().execute(Print("\n"))?;
+
+    Ok(())
+}
+
+fn write_pbm(nonogram: &Nonogram<char>, out: &mut impl Write) -> Result<(), CliError> {
+    writeln!(out, "P1\n{} {}", nonogram.cols(), nonogram.rows())?;
+
+    for row in nonogram {
+        let pixels: Vec<_> = row
+            .iter()
+            .map(|cell| if cell.is_box() { "1" } else { "0" })
+            .collect();
+
+        writeln!(out, "{}", pixels.join(" "))?;
+    }
+    Ok(())
+}
+
+fn parse_range(range: &str) -> Result<Range<usize>, String> {
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| format!("expected a range like \"1..3\", found \"{}\"", range))?;
+
+    let start = start.parse().map_err(|e| format!("{}", e))?;
+    let end = end.parse().map_err(|e| format!("{}", e))?;
+
+    Ok(start..end)
+}
+
+fn print_nonogram(
+    nonogram: Nonogram<char>,
+    index: usize,
+    total: usize,
+    palette: &Palette,
+) -> Result<(), CliError> {
+    print_header(&nonogram, index, total)?;
+
+    for row in 0..nonogram.rows() {
+        print_row(&nonogram, row, 2, palette)?;
+    }
+    stdout().queue(Print("\n"))?;
+
+    Ok(())
+}
+
+/// Prints a nonogram with the column clues above and the row clues to the left.
+fn print_grid(
+    layout: &Layout<char>,
+    nonogram: Nonogram<char>,
+    index: usize,
+    total: usize,
+    palette: &Palette,
+) -> Result<(), CliError> {
+    let clues = |items: &[Item<char>]| {
+        let mut lens: Vec<_> = items
+            .iter()
+            .filter(|item| item.len > 0)
+            .map(|item| item.len.to_string())
+            .collect();
+
+        if lens.is_empty() {
+            lens.push("0".to_string());
+        }
+        lens
+    };
+    let col_clues: Vec<_> = layout.col_clues().map(clues).collect();
+    let row_clues: Vec<_> = layout
+        .row_clues()
+        .map(|items| clues(items).join(" "))
+        .collect();
+    let height = col_clues.iter().map(Vec::len).max().unwrap_or(0);
+    let margin = row_clues.iter().map(String::len).max().unwrap_or(0) + 1;
+    let width = col_clues
+        .iter()
+        .flatten()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(2);
+
+    print_header(&nonogram, index, total)?;
+
+    for line in 0..height {
+        stdout().queue(Print(" ".repeat(margin)))?;
+
+        for clues in &col_clues {
+            let clue = (line + clues.len())
+                .checked_sub(height)
+                .map(|i| clues[i].as_str())
+                .unwrap_or("");
+
+            stdout().queue(Print(format!("{:>width$}", clue)))?;
+        }
         stdout().queue(Print("\n"))?;
     }
-    stdout().queue(SetForegroundColor(Color::Reset))?;
+    for (row, clues) in row_clues.iter().enumerate().take(nonogram.rows()) {
+        stdout().queue(Print(format!("{:>width$} ", clues, width = margin - 1)))?;
+
+        print_row(&nonogram, row, width, palette)?;
+    }
+    stdout().queue(Print("\n"))?;
 
     Ok(())
 }
 
-fn map_color(color: char) -> Result<Color, CliError> {
+/// Prints a line like `=== Solution 1/3 (10×15) ===`, padded to the width of the grid.
+fn print_header(nonogram: &Nonogram<char>, index: usize, total: usize) -> Result<(), CliError> {
+    let label = format!(
+        " Solution {}/{} ({}×{}) ",
+        index + 1,
+        total,
+        nonogram.cols(),
+        nonogram.rows()
+    );
+    let width = (nonogram.cols() * 2).max(label.chars().count() + 6);
+
+    stdout().queue(Print(format!("{label:=^width$}\n")))?;
+
+    Ok(())
+}
+
+/// Prints a row with each cell `width` characters wide.
+fn print_row(
+    nonogram: &Nonogram<char>,
+    row: usize,
+    width: usize,
+    palette: &Palette,
+) -> Result<(), CliError> {
+    for col in 0..nonogram.cols() {
+        match nonogram[(col, row)] {
+            Cell::Box { color } => {
+                let c = map_color(color, palette)?;
+
+                stdout().queue(SetForegroundColor(c))?;
+                stdout().queue(Print("█".repeat(width)))?;
+            }
+            Cell::Space => {
+                stdout().queue(Print(" ".repeat(width)))?;
+            }
+        }
+    }
+    stdout()
+        .queue(SetForegroundColor(Color::Reset))?
+        .queue(Print("\n"))?;
+
+    Ok(())
+}
+
+fn map_color(color: char, palette: &Palette) -> Result<Color, CliError> {
+    if let Some(c) = palette.get(&color) {
+        return Ok(*c);
+    }
     match color {
         '!' => Ok(Color::Reset),
         '0' => Ok(Color::Black),
@@ -136,6 +535,86 @@ fn map_color(color: char) -> Result<Color, CliError> {
         'b' => Ok(Color::DarkBlue),
         'm' => Ok(Color::DarkMagenta),
         'c' => Ok(Color::DarkCyan),
-        color => Err(CliError::InvalidColor { color }),
+        color => Err(CliError::InvalidColor {
+            color: color.to_string(),
+        }),
+    }
+}
+
+/// Parses a color like `#FF0000`.
+fn parse_hex_color(hex: &str) -> Result<Color, CliError> {
+    let invalid = || CliError::InvalidColor {
+        color: hex.to_string(),
+    };
+    let digits = hex.strip_prefix('#').ok_or_else(invalid)?;
+
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+
+    Ok(Color::Rgb {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+    })
+}
+
+fn parse_palette(palette: &str) -> Result<Palette, String> {
+    let hex: HashMap<char, String> = serde_json::from_str(palette).map_err(|e| format!("{}", e))?;
+
+    hex.into_iter()
+        .map(|(color, hex)| {
+            Ok((
+                color,
+                parse_hex_color(&hex).map_err(|e| format!("{:?}", e))?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_hex_color_rgb() {
+        let color = parse_hex_color("#FF0000").unwrap();
+
+        assert_eq!(Color::Rgb { r: 255, g: 0, b: 0 }, color);
+        assert_eq!(
+            Color::Rgb {
+                r: 0x12,
+                g: 0xab,
+                b: 0xCD
+            },
+            parse_hex_color("#12abCD").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_hex_color_invalid() {
+        for hex in ["FF0000", "#FF00", "#FF00000", "#GG0000", "#+F0000", "#ÄÄÄ"] {
+            assert!(matches!(
+                parse_hex_color(hex),
+                Err(CliError::InvalidColor { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn map_color_palette() {
+        let palette = parse_palette(r##"{"R": "#00FF00", "x": "#000000"}"##).unwrap();
+
+        assert_eq!(
+            Color::Rgb { r: 0, g: 255, b: 0 },
+            map_color('R', &palette).unwrap()
+        );
+        assert_eq!(
+            Color::Rgb { r: 0, g: 0, b: 0 },
+            map_color('x', &palette).unwrap()
+        );
+        assert_eq!(Color::Green, map_color('G', &palette).unwrap());
+        assert!(map_color('x', &Palette::new()).is_err());
     }
 }
@@ -1,9 +1,14 @@
+use std::sync::Arc;
+
 /// Placeholder error type for cancellation.
 #[derive(Default)]
 pub struct Cancelled;
 
 /// A trait for an arbitrary cancellation token.
 /// Use `()`, if you don't have any cancellation token.
+///
+/// The trait is object safe, so tokens can be type-erased as `Box<dyn Token>` or `&dyn Token`.
+/// New methods must keep it that way.
 pub trait Token: Send + Sync {
     /// Returns [Cancelled], if the operation has been cancelled.
     fn check(&self) -> Result<(), Cancelled>;
@@ -15,6 +20,27 @@ impl Token for () {
     }
 }
 
+/// Shares a token between multiple concurrent operations.
+impl<T: Token + ?Sized> Token for Arc<T> {
+    fn check(&self) -> Result<(), Cancelled> {
+        (**self).check()
+    }
+}
+
+/// Allows a type-erased `Box<dyn Token>` to be used as a token.
+impl<T: Token + ?Sized> Token for Box<T> {
+    fn check(&self) -> Result<(), Cancelled> {
+        (**self).check()
+    }
+}
+
+/// Allows a borrowed token, including `&dyn Token`, to be used as a token.
+impl<T: Token + ?Sized> Token for &T {
+    fn check(&self) -> Result<(), Cancelled> {
+        (**self).check()
+    }
+}
+
 #[cfg(test)]
 #[derive(Default)]
 pub struct Cancel;
@@ -22,16 +48,129 @@ pub struct Cancel;
 #[cfg(test)]
 impl Token for Cancel {
     fn check(&self) -> Result<(), Cancelled> {
-        Err(Cancelled::default())
+        Err(Cancelled)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::collection::{Collection, Collector};
+    use crate::algo::{Branch, Error};
+    use crate::{Item, Layout, Nonogram, Solution, Status};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+
+    /// A token which can be cancelled through a shared reference.
+    #[derive(Default)]
+    struct Flag(AtomicBool);
+
+    impl Token for Flag {
+        fn check(&self) -> Result<(), Cancelled> {
+            if self.0.load(Ordering::Relaxed) {
+                Err(Cancelled)
+            } else {
+                Ok(())
+            }
+        }
+    }
 
     #[test]
     fn check_tuple() {
         assert!(matches!(().check(), Ok(())));
     }
+
+    #[test]
+    fn check_arc() {
+        let token = Arc::new(Flag::default());
+
+        assert!(matches!(token.check(), Ok(())));
+
+        token.0.store(true, Ordering::Relaxed);
+
+        assert!(matches!(token.check(), Err(Cancelled)));
+    }
+
+    #[test]
+    fn check_dyn() {
+        let boxed: Box<dyn Token> = Box::new(Cancel);
+        let borrowed: &dyn Token = &();
+
+        assert!(matches!(boxed.check(), Err(Cancelled)));
+        assert!(matches!(borrowed.check(), Ok(())));
+    }
+
+    #[test]
+    fn solve_boxed() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 1)]; 2],
+            vec![vec![Item::new('a', 1)]; 2],
+        );
+        let token: Box<dyn Token> = Box::new(Cancel);
+
+        let solution = layout.clone().solve(usize::MAX, token);
+
+        assert!(matches!(solution.status, Status::Cancelled));
+
+        let token: Box<dyn Token> = Box::new(());
+
+        let solution = layout.solve(usize::MAX, token);
+
+        assert!(matches!(solution.status, Status::Complete));
+        assert_eq!(2, solution.len());
+    }
+
+    /// Cancels a shared token once it has collected a solution.
+    struct CancelOnPush<'a> {
+        collection: Collection<char, Arc<Flag>>,
+        token: &'a Flag,
+    }
+
+    impl Collector<char> for CancelOnPush<'_> {
+        fn push(&self, nonogram: Nonogram<char>) {
+            self.collection.push(nonogram);
+            self.token.0.store(true, Ordering::Relaxed);
+        }
+
+        fn check(&self) -> Result<(), Error> {
+            self.collection.check()
+        }
+    }
+
+    #[test]
+    fn solve_arc_shared() {
+        let token = Arc::new(Flag::default());
+        // Every permutation of 6 boxes is a solution.
+        let lines = vec![vec![Item::new('a', 1)]; 6];
+
+        let solutions: Vec<Solution<char>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let lines = lines.clone();
+                    let token = &token;
+
+                    s.spawn(move || {
+                        let collector = CancelOnPush {
+                            collection: Collection::new(usize::MAX, token.clone()),
+                            token,
+                        };
+                        Branch::build(lines.clone(), lines).solve(&collector);
+
+                        collector.collection.into()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        assert!(solutions
+            .iter()
+            .all(|solution| matches!(solution.status, Status::Cancelled)));
+        assert!(solutions.iter().all(|solution| solution.len() < 720));
+        assert!(solutions.iter().any(|solution| !solution.is_empty()));
+    }
 }
@@ -0,0 +1,336 @@
+//! A compact, URL-safe format for layouts.
+//!
+//! The layout is written as a sequence of 4-bit nibbles, which is then encoded as base64url without padding.
+//!
+//! * Numbers are written in groups of three bits, least significant first.
+//!   The fourth bit of a nibble marks that another group follows.
+//! * The header consists of the column count, the row count and the color mode.
+//!   If all items share a color, the mode is `0` followed by the color.
+//!   Otherwise the mode is `1` and every item is followed by its color.
+//! * Every line is a sequence of item lengths terminated by `0`.
+//!   Lengths from `1` to `14` take a single nibble,
+//!   longer ones are written as `15` followed by the length minus `15` as a number.
+//!
+//! Items of length zero are dropped, as they don't affect the solution.
+
+use crate::{Item, Layout};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+const END: u8 = 0;
+const ESCAPE: u8 = 15;
+const UNIFORM: usize = 0;
+const MIXED: usize = 1;
+
+/// A reason why a string could not be decoded into a [Layout].
+#[derive(Clone, PartialEq, Debug)]
+pub enum DecodeError {
+    /// The string is not valid base64url.
+    InvalidBase64,
+    /// The data ended before the layout was complete.
+    UnexpectedEnd,
+    /// There is data left after the layout.
+    TrailingData,
+    /// A number doesn't fit into `usize`.
+    Overflow,
+    /// The color mode is unknown.
+    InvalidMode(usize),
+}
+
+impl Layout<usize> {
+    /// Encodes the layout as a compact, URL-safe string.
+    ///
+    /// The same layout always results in the same string.
+    pub fn to_compact_string(&self) -> String {
+        let mut writer = Writer::default();
+        let mut colors = self.cols.iter().chain(&self.rows).flatten();
+        let first = colors.next().map(|item| item.color).unwrap_or(0);
+        let uniform = colors.all(|item| item.color == first);
+
+        writer.number(self.cols.len());
+        writer.number(self.rows.len());
+
+        if uniform {
+            writer.number(UNIFORM);
+            writer.number(first);
+        } else {
+            writer.number(MIXED);
+        }
+        for items in self.cols.iter().chain(&self.rows) {
+            for item in items.iter().filter(|item| item.len > 0) {
+                writer.len(item.len);
+
+                if !uniform {
+                    writer.number(item.color);
+                }
+            }
+            writer.nibble(END);
+        }
+        URL_SAFE_NO_PAD.encode(writer.finish())
+    }
+
+    /// Decodes a layout from a string created by [Layout::to_compact_string].
+    pub fn from_compact_string(s: &str) -> Result<Self, DecodeError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| DecodeError::InvalidBase64)?;
+        let mut reader = Reader::new(&bytes);
+
+        let col_count = reader.number()?;
+        let row_count = reader.number()?;
+        let uniform = match reader.number()? {
+            UNIFORM => Some(reader.number()?),
+            MIXED => None,
+            mode => return Err(DecodeError::InvalidMode(mode)),
+        };
+        let mut lines = Vec::new();
+
+        for _ in 0..col_count.saturating_add(row_count) {
+            let mut items = Vec::new();
+
+            while let Some(len) = reader.len()? {
+                let color = match uniform {
+                    Some(color) => color,
+                    None => reader.number()?,
+                };
+                items.push(Item::new(color, len));
+            }
+            lines.push(items);
+        }
+        reader.finish()?;
+
+        let rows = lines.split_off(col_count);
+
+        Ok(Layout { cols: lines, rows })
+    }
+}
+
+/// Writes nibbles into bytes, high nibble first.
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+    half: bool,
+}
+
+impl Writer {
+    fn nibble(&mut self, nibble: u8) {
+        if self.half {
+            *self.bytes.last_mut().unwrap() |= nibble;
+        } else {
+            self.bytes.push(nibble << 4);
+        }
+        self.half = !self.half;
+    }
+
+    fn number(&mut self, mut value: usize) {
+        loop {
+            let group = (value & 0b111) as u8;
+            value >>= 3;
+
+            if value == 0 {
+                return self.nibble(group);
+            }
+            self.nibble(group | 0b1000);
+        }
+    }
+
+    fn len(&mut self, len: usize) {
+        if len < ESCAPE as usize {
+            self.nibble(len as u8);
+        } else {
+            self.nibble(ESCAPE);
+            self.number(len - ESCAPE as usize);
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads nibbles from bytes, high nibble first.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, index: 0 }
+    }
+
+    fn nibble(&mut self) -> Result<u8, DecodeError> {
+        let byte = self
+            .bytes
+            .get(self.index / 2)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        let nibble = if self.index.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0b1111
+        };
+        self.index += 1;
+
+        Ok(nibble)
+    }
+
+    fn number(&mut self) -> Result<usize, DecodeError> {
+        let mut value: usize = 0;
+        let mut shift = 0;
+
+        loop {
+            let nibble = self.nibble()?;
+            let group = (nibble & 0b111) as usize;
+
+            if shift >= usize::BITS || (group << shift) >> shift != group {
+                return Err(DecodeError::Overflow);
+            }
+            value |= group << shift;
+            shift += 3;
+
+            if nibble & 0b1000 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Reads the length of an item or `None` at the end of a line.
+    fn len(&mut self) -> Result<Option<usize>, DecodeError> {
+        match self.nibble()? {
+            END => Ok(None),
+            ESCAPE => self
+                .number()?
+                .checked_add(ESCAPE as usize)
+                .map(Some)
+                .ok_or(DecodeError::Overflow),
+            len => Ok(Some(len as usize)),
+        }
+    }
+
+    /// Checks that at most a padding nibble is left.
+    fn finish(mut self) -> Result<(), DecodeError> {
+        match self.bytes.len() * 2 - self.index {
+            0 => Ok(()),
+            1 if self.nibble()? == 0 => Ok(()),
+            _ => Err(DecodeError::TrailingData),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn line(lens: &[usize], color: usize) -> Vec<Item<usize>> {
+        lens.iter().map(|len| Item::new(color, *len)).collect()
+    }
+
+    #[test]
+    fn compact_string_round_trip() {
+        let cols = vec![line(&[1, 2], 0), Vec::new(), line(&[3], 0)];
+        let rows = vec![line(&[2], 0), line(&[1, 3], 0)];
+        let layout = Layout::new(cols, rows);
+
+        let text = layout.to_compact_string();
+
+        assert_eq!(layout, Layout::from_compact_string(&text).unwrap());
+    }
+
+    #[test]
+    fn compact_string_round_trip_colors() {
+        let cols = vec![vec![Item::new(3, 1), Item::new(700, 2)]];
+        let rows = vec![line(&[15, 100], 9), Vec::new()];
+        let layout = Layout::new(cols, rows);
+
+        let text = layout.to_compact_string();
+
+        assert_eq!(layout, Layout::from_compact_string(&text).unwrap());
+    }
+
+    #[test]
+    fn compact_string_round_trip_empty() {
+        let layout = Layout::new(Vec::new(), Vec::new());
+
+        let text = layout.to_compact_string();
+
+        assert_eq!(layout, Layout::from_compact_string(&text).unwrap());
+    }
+
+    #[test]
+    fn compact_string_stable() {
+        let layout = Layout::new(vec![line(&[1, 1], 0)], vec![line(&[1], 0); 3]);
+
+        assert_eq!("EwARAQEBAA", layout.to_compact_string());
+    }
+
+    #[test]
+    fn compact_string_url_safe() {
+        let cols = vec![line(&[14, 15, 200], 63); 30];
+        let text = Layout::new(cols, Vec::new()).to_compact_string();
+
+        assert!(text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn compact_string_short() {
+        let layout = Layout::new(vec![line(&[3, 1, 4], 0); 25], vec![line(&[5, 2, 6], 0); 25]);
+
+        assert!(layout.to_compact_string().len() < 200);
+    }
+
+    #[test]
+    fn compact_string_invalid_base64() {
+        let result = Layout::from_compact_string("a+b");
+
+        assert_eq!(Some(DecodeError::InvalidBase64), result.err());
+    }
+
+    #[test]
+    fn compact_string_unexpected_end() {
+        let text = Layout::new(vec![line(&[1, 1], 0)], Vec::new()).to_compact_string();
+        let bytes = URL_SAFE_NO_PAD.decode(text).unwrap();
+        let text = URL_SAFE_NO_PAD.encode(&bytes[..bytes.len() - 1]);
+
+        assert_eq!(
+            Some(DecodeError::UnexpectedEnd),
+            Layout::from_compact_string(&text).err()
+        );
+    }
+
+    #[test]
+    fn compact_string_trailing_data() {
+        let text = Layout::new(vec![line(&[1], 0)], Vec::new()).to_compact_string();
+        let mut bytes = URL_SAFE_NO_PAD.decode(text).unwrap();
+        bytes.push(0);
+        let text = URL_SAFE_NO_PAD.encode(bytes);
+
+        assert_eq!(
+            Some(DecodeError::TrailingData),
+            Layout::from_compact_string(&text).err()
+        );
+    }
+
+    #[test]
+    fn compact_string_invalid_mode() {
+        // Zero columns, zero rows, mode 2.
+        let text = URL_SAFE_NO_PAD.encode([0x00, 0x20]);
+
+        assert_eq!(
+            Some(DecodeError::InvalidMode(2)),
+            Layout::from_compact_string(&text).err()
+        );
+    }
+
+    #[test]
+    fn compact_string_overflow() {
+        // A number with more groups than `usize` can hold.
+        let text = URL_SAFE_NO_PAD.encode([0xff; 16]);
+
+        assert_eq!(
+            Some(DecodeError::Overflow),
+            Layout::from_compact_string(&text).err()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! An importer for puzzles exported by Griddlers.net.
+//!
+//! The export is a JSON object with the size of the puzzle and its clues.
+//! Every clue is a `[count, color]` pair, where the color is a string.
+//!
+//! ```json
+//! {
+//!     "width": 2,
+//!     "height": 1,
+//!     "columns": [[[1, "black"]], []],
+//!     "rows": [[[1, "black"]]]
+//! }
+//! ```
+
+use crate::{Item, Layout};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// A reason why a Griddlers.net export could not be imported.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GriddlersError {
+    /// The text is not valid JSON or doesn't follow the schema.
+    JsonParse(String),
+    /// A color could not be mapped.
+    UnknownColor(String),
+    /// The amount of columns or rows doesn't match the size of the puzzle.
+    StructureMismatch,
+}
+
+/// Maps the color strings of an export to colors.
+pub trait ColorMapper<T> {
+    /// Returns the color for a string or `None` if it is unknown.
+    fn map(&self, color: &str) -> Option<T>;
+}
+
+impl<T, F: Fn(&str) -> Option<T>> ColorMapper<T> for F {
+    fn map(&self, color: &str) -> Option<T> {
+        self(color)
+    }
+}
+
+#[derive(Deserialize)]
+struct Export {
+    width: usize,
+    height: usize,
+    columns: Vec<Vec<(usize, String)>>,
+    rows: Vec<Vec<(usize, String)>>,
+}
+
+/// Imports a Griddlers.net export, parsing the colors with [FromStr].
+pub fn from_griddlers_json<T: FromStr + Copy>(json: &str) -> Result<Layout<T>, GriddlersError> {
+    from_griddlers_json_with(json, &|color: &str| color.parse().ok())
+}
+
+/// Imports a Griddlers.net export, mapping the colors with a custom [ColorMapper].
+pub fn from_griddlers_json_with<T>(
+    json: &str,
+    mapper: &impl ColorMapper<T>,
+) -> Result<Layout<T>, GriddlersError> {
+    let export: Export =
+        serde_json::from_str(json).map_err(|e| GriddlersError::JsonParse(e.to_string()))?;
+
+    if export.columns.len() != export.width || export.rows.len() != export.height {
+        return Err(GriddlersError::StructureMismatch);
+    }
+    let convert = |lines: Vec<Vec<(usize, String)>>| {
+        lines
+            .into_iter()
+            .map(|clues| {
+                clues
+                    .into_iter()
+                    .map(|(len, color)| match mapper.map(&color) {
+                        Some(color) => Ok(Item::new(color, len)),
+                        None => Err(GriddlersError::UnknownColor(color)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    Ok(Layout {
+        cols: convert(export.columns)?,
+        rows: convert(export.rows)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Cell;
+
+    const HEART: &str = r##"{
+        "width": 3,
+        "height": 3,
+        "columns": [[[2, "r"]], [[2, "r"]], [[2, "r"]]],
+        "rows": [[[1, "r"], [1, "r"]], [[3, "r"]], [[1, "r"]]]
+    }"##;
+
+    #[test]
+    fn from_griddlers_json_solve() {
+        let layout: Layout<char> = from_griddlers_json(HEART).unwrap();
+        let nonogram = layout.solve_unique(()).unwrap();
+
+        assert_eq!(Cell::Box { color: 'r' }, nonogram[(0, 0)]);
+        assert_eq!(Cell::Space, nonogram[(1, 0)]);
+        assert_eq!(Cell::Box { color: 'r' }, nonogram[(1, 2)]);
+        assert_eq!(6, nonogram.box_count());
+    }
+
+    #[test]
+    fn from_griddlers_json_with_mapper() {
+        let json =
+            r#"{"width": 1, "height": 1, "columns": [[[1, "black"]]], "rows": [[[1, "black"]]]}"#;
+        let mapper = |color: &str| (color == "black").then_some(true);
+        let layout = from_griddlers_json_with(json, &mapper).unwrap();
+
+        assert_eq!(vec![vec![Item::new(true, 1)]], layout.cols);
+    }
+
+    #[test]
+    fn from_griddlers_json_unknown_color() {
+        let json = r#"{"width": 1, "height": 0, "columns": [[[1, "red"]]], "rows": []}"#;
+
+        assert_eq!(
+            Err(GriddlersError::UnknownColor("red".to_string())),
+            from_griddlers_json::<char>(json)
+        );
+    }
+
+    #[test]
+    fn from_griddlers_json_structure_mismatch() {
+        let json = r#"{"width": 2, "height": 0, "columns": [[]], "rows": []}"#;
+
+        assert_eq!(
+            Err(GriddlersError::StructureMismatch),
+            from_griddlers_json::<char>(json)
+        );
+    }
+
+    #[test]
+    fn from_griddlers_json_invalid() {
+        let result = from_griddlers_json::<char>("{\"width\": 1");
+
+        assert!(matches!(result, Err(GriddlersError::JsonParse(_))));
+    }
+}
@@ -0,0 +1,7 @@
+//! Alternative formats for layouts besides `serde`.
+
+#[cfg(feature = "compact")]
+pub mod compact;
+#[cfg(feature = "format_griddlers")]
+pub mod griddlers;
+pub mod text;
@@ -0,0 +1,255 @@
+//! A plain text format for single-colored layouts.
+//!
+//! The layout consists of a `cols:` and a `rows:` line.
+//! Lines are separated by `|` and their items by whitespace.
+//! An empty line is written as `0`.
+//!
+//! ```text
+//! cols: 1 2 | 3
+//! rows: 2 | 1 3
+//! ```
+
+use crate::layout::write_items;
+use crate::{Item, Layout};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The color of all items of a parsed layout.
+///
+/// This matches the filled color of [Layout::to_usize_layout],
+/// so parsed layouts convert to a `Layout<bool>`.
+const COLOR: usize = 1;
+
+/// A reason why a text could not be parsed into a [Layout].
+#[derive(Clone, PartialEq, Debug)]
+pub enum LayoutParseError {
+    /// The text doesn't contain anything.
+    Empty,
+    /// The `cols:` or `rows:` section is missing.
+    MissingSection(&'static str),
+    /// A section appears more than once.
+    DuplicateSection(&'static str),
+    /// A line doesn't start with a known section.
+    UnknownSection(String),
+    /// An item is not a non-negative integer.
+    InvalidToken(String),
+}
+
+impl TryFrom<&str> for Layout<usize> {
+    type Error = LayoutParseError;
+
+    fn try_from(text: &str) -> Result<Self, Self::Error> {
+        let mut cols = None;
+        let mut rows = None;
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (name, rest, target) = if let Some(rest) = line.strip_prefix("cols:") {
+                ("cols", rest, &mut cols)
+            } else if let Some(rest) = line.strip_prefix("rows:") {
+                ("rows", rest, &mut rows)
+            } else {
+                return Err(LayoutParseError::UnknownSection(line.to_string()));
+            };
+
+            if target.is_some() {
+                return Err(LayoutParseError::DuplicateSection(name));
+            }
+            *target = Some(parse_lines(rest)?);
+        }
+        match (cols, rows) {
+            (None, None) => Err(LayoutParseError::Empty),
+            (None, _) => Err(LayoutParseError::MissingSection("cols")),
+            (_, None) => Err(LayoutParseError::MissingSection("rows")),
+            (Some(cols), Some(rows)) => Ok(Layout { cols, rows }),
+        }
+    }
+}
+
+impl FromStr for Layout<usize> {
+    type Err = LayoutParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Layout::try_from(text)
+    }
+}
+
+impl Display for Layout<usize> {
+    /// Writes the layout in the text format, without a trailing newline.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "cols: ")?;
+        write_lines(f, &self.cols)?;
+        write!(f, "\nrows: ")?;
+        write_lines(f, &self.rows)
+    }
+}
+
+impl Layout<usize> {
+    /// Writes the layout in the text format.
+    ///
+    /// The colors are dropped, as the format doesn't support them.
+    pub fn to_text(&self) -> String {
+        format!("{}\n", self)
+    }
+}
+
+/// Parses the lines of a section.
+fn parse_lines(text: &str) -> Result<Vec<Vec<Item<usize>>>, LayoutParseError> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    text.split('|').map(parse_items).collect()
+}
+
+/// Parses the items of a line, skipping items of length zero.
+fn parse_items(text: &str) -> Result<Vec<Item<usize>>, LayoutParseError> {
+    let mut items = Vec::new();
+
+    for token in text.split_whitespace() {
+        let len: usize = token
+            .parse()
+            .map_err(|_| LayoutParseError::InvalidToken(token.to_string()))?;
+
+        if len > 0 {
+            items.push(Item::new(COLOR, len));
+        }
+    }
+    Ok(items)
+}
+
+/// Writes the lines of a section.
+fn write_lines<T>(f: &mut Formatter<'_>, lines: &[Vec<Item<T>>]) -> fmt::Result {
+    for (index, items) in lines.iter().enumerate() {
+        if index > 0 {
+            write!(f, " | ")?;
+        }
+        write_items(f, items, |f, item| write!(f, "{}", item.len))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lens(lines: &[Vec<Item<usize>>]) -> Vec<Vec<usize>> {
+        lines
+            .iter()
+            .map(|items| items.iter().map(|item| item.len).collect())
+            .collect()
+    }
+
+    #[test]
+    fn layout_try_from_str() {
+        let layout = Layout::try_from("cols: 1 2 | 3\nrows: 2 | 1 3").unwrap();
+
+        assert_eq!(vec![vec![1, 2], vec![3]], lens(&layout.cols));
+        assert_eq!(vec![vec![2], vec![1, 3]], lens(&layout.rows));
+        assert!(layout.cols.iter().flatten().all(|item| item.color == COLOR));
+    }
+
+    #[test]
+    fn layout_try_from_str_empty_line() {
+        let layout = Layout::try_from("rows: 0 | 1\ncols: 1 | 0").unwrap();
+
+        assert_eq!(vec![vec![1], vec![]], lens(&layout.cols));
+        assert_eq!(vec![vec![], vec![1]], lens(&layout.rows));
+    }
+
+    #[test]
+    fn layout_try_from_str_empty() {
+        let result = Layout::try_from(" \n ");
+
+        assert_eq!(Some(LayoutParseError::Empty), result.err());
+    }
+
+    #[test]
+    fn layout_try_from_str_missing_section() {
+        let result = Layout::try_from("cols: 1 | 1");
+
+        assert_eq!(Some(LayoutParseError::MissingSection("rows")), result.err());
+    }
+
+    #[test]
+    fn layout_try_from_str_duplicate_section() {
+        let result = Layout::try_from("cols: 1\nrows: 1\ncols: 1");
+
+        assert_eq!(
+            Some(LayoutParseError::DuplicateSection("cols")),
+            result.err()
+        );
+    }
+
+    #[test]
+    fn layout_try_from_str_unknown_section() {
+        let result = Layout::try_from("cols: 1\nlines: 1");
+
+        assert_eq!(
+            Some(LayoutParseError::UnknownSection("lines: 1".to_string())),
+            result.err()
+        );
+    }
+
+    #[test]
+    fn layout_try_from_str_invalid_token() {
+        let result = Layout::try_from("cols: 1 a\nrows: 1");
+
+        assert_eq!(
+            Some(LayoutParseError::InvalidToken("a".to_string())),
+            result.err()
+        );
+    }
+
+    #[test]
+    fn layout_to_text() {
+        let layout = Layout::try_from("cols: 1 2 | 3\nrows: 2 | 1 3").unwrap();
+
+        assert_eq!("cols: 1 2 | 3\nrows: 2 | 1 3\n", layout.to_text());
+    }
+
+    #[test]
+    fn layout_parse() {
+        let layout: Layout<usize> = "cols: 2 | 1 | 1\nrows: 1 1 | 2".parse().unwrap();
+        let expected = Layout::new(
+            vec![
+                vec![Item::new(COLOR, 2)],
+                vec![Item::new(COLOR, 1)],
+                vec![Item::new(COLOR, 1)],
+            ],
+            vec![
+                vec![Item::new(COLOR, 1), Item::new(COLOR, 1)],
+                vec![Item::new(COLOR, 2)],
+            ],
+        );
+
+        assert_eq!(expected, layout);
+        assert_eq!(
+            expected.solve_unique(()).unwrap(),
+            layout.solve_unique(()).unwrap()
+        );
+    }
+
+    #[test]
+    fn layout_parse_error() {
+        let result = "rows: 1".parse::<Layout<usize>>();
+
+        assert_eq!(Err(LayoutParseError::MissingSection("cols")), result);
+    }
+
+    #[test]
+    fn layout_display_round_trip() {
+        let layout = Layout::try_from("cols: 1 1 | 0 | 2\nrows: 3 | | 1").unwrap();
+        let text = layout.to_string();
+
+        assert_eq!("cols: 1 1 | 0 | 2\nrows: 3 | 0 | 1", text);
+        assert_eq!(layout, text.parse().unwrap());
+    }
+
+    #[test]
+    fn layout_text_round_trip() {
+        let text = "cols: 1 1 | 0 | 2\nrows: 3 | | 1";
+        let layout = Layout::try_from(text).unwrap();
+        let parsed = Layout::try_from(layout.to_text().as_str()).unwrap();
+
+        assert_eq!(layout, parsed);
+    }
+}
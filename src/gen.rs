@@ -0,0 +1,206 @@
+//! Random generation of layouts.
+
+use crate::{Cell, Layout, Nonogram};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// The symmetry of a generated nonogram.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SymmetryKind {
+    /// The nonogram stays the same when rotated by 180°.
+    Rotation180,
+    /// The left and right half mirror each other.
+    MirrorH,
+    /// The top and bottom half mirror each other.
+    MirrorV,
+    /// Both halves mirror each other horizontally and vertically.
+    All4,
+}
+
+impl SymmetryKind {
+    /// Returns the positions a cell is mapped to, including itself.
+    fn orbit(self, cols: usize, rows: usize, col: usize, row: usize) -> Vec<(usize, usize)> {
+        let (mirror_col, mirror_row) = (cols - 1 - col, rows - 1 - row);
+
+        match self {
+            SymmetryKind::Rotation180 => vec![(col, row), (mirror_col, mirror_row)],
+            SymmetryKind::MirrorH => vec![(col, row), (mirror_col, row)],
+            SymmetryKind::MirrorV => vec![(col, row), (col, mirror_row)],
+            SymmetryKind::All4 => vec![
+                (col, row),
+                (mirror_col, row),
+                (col, mirror_row),
+                (mirror_col, mirror_row),
+            ],
+        }
+    }
+}
+
+/// Generates a random layout.
+///
+/// Every cell is a box with a random color of `colors` or a space with equal probability.
+/// If `colors` is empty, all cells are spaces.
+/// The layout isn't guaranteed to have a unique solution, which can be checked with [Layout::solve_unique].
+///
+/// # Parameters
+/// * `cols`: The column count.
+/// * `rows`: The row count.
+/// * `colors`: The colors of the boxes.
+/// * `rng`: The source of randomness.
+pub fn random<T: Copy + PartialEq, R: Rng>(
+    cols: usize,
+    rows: usize,
+    colors: &[T],
+    rng: &mut R,
+) -> Layout<T> {
+    let mut nonogram = Nonogram::new(cols, rows);
+
+    for row in 0..rows {
+        for col in 0..cols {
+            nonogram[(col, row)] = random_cell(colors, rng);
+        }
+    }
+    Layout::from(&nonogram)
+}
+
+/// Generates a random layout whose solution has the given symmetry.
+///
+/// Every cell is a box with a random color of `colors` or a space with equal probability.
+/// If `colors` is empty, all cells are spaces.
+/// The layout isn't guaranteed to have a unique solution, which can be checked with [Layout::solve_unique].
+///
+/// # Parameters
+/// * `cols`: The column count.
+/// * `rows`: The row count.
+/// * `colors`: The colors of the boxes.
+/// * `symmetry`: The symmetry of the solution.
+/// * `rng`: The source of randomness.
+pub fn symmetric_random<T: Copy + PartialEq, R: Rng>(
+    cols: usize,
+    rows: usize,
+    colors: &[T],
+    symmetry: SymmetryKind,
+    rng: &mut R,
+) -> Layout<T> {
+    Layout::from(&symmetric_nonogram(cols, rows, colors, symmetry, rng))
+}
+
+/// Generates a random nonogram with the given symmetry.
+fn symmetric_nonogram<T: Copy, R: Rng>(
+    cols: usize,
+    rows: usize,
+    colors: &[T],
+    symmetry: SymmetryKind,
+    rng: &mut R,
+) -> Nonogram<T> {
+    let mut nonogram = Nonogram::new(cols, rows);
+    let mut done = vec![false; cols * rows];
+
+    for row in 0..rows {
+        for col in 0..cols {
+            if done[row * cols + col] {
+                continue;
+            }
+            let cell = random_cell(colors, rng);
+
+            for (col, row) in symmetry.orbit(cols, rows, col, row) {
+                nonogram[(col, row)] = cell;
+                done[row * cols + col] = true;
+            }
+        }
+    }
+    nonogram
+}
+
+/// Picks a box with a random color or a space with equal probability.
+fn random_cell<T: Copy, R: Rng>(colors: &[T], rng: &mut R) -> Cell<T> {
+    match colors.choose(rng) {
+        Some(color) if rng.gen_bool(0.5) => Cell::Box { color: *color },
+        _ => Cell::Space,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const KINDS: [SymmetryKind; 4] = [
+        SymmetryKind::Rotation180,
+        SymmetryKind::MirrorH,
+        SymmetryKind::MirrorV,
+        SymmetryKind::All4,
+    ];
+
+    fn is_symmetric<T: PartialEq>(nonogram: &Nonogram<T>, symmetry: SymmetryKind) -> bool {
+        let (cols, rows) = (nonogram.cols(), nonogram.rows());
+
+        (0..rows).all(|row| {
+            (0..cols).all(|col| {
+                symmetry
+                    .orbit(cols, rows, col, row)
+                    .into_iter()
+                    .all(|pos| nonogram[pos] == nonogram[(col, row)])
+            })
+        })
+    }
+
+    #[test]
+    fn symmetric_nonogram_symmetric() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for symmetry in KINDS {
+            for (cols, rows) in [(5, 5), (4, 7), (1, 1), (0, 3)] {
+                let nonogram = symmetric_nonogram(cols, rows, &['a', 'b'], symmetry, &mut rng);
+
+                assert!(is_symmetric(&nonogram, symmetry));
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_random_solve() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..10 {
+            let layout = symmetric_random(6, 5, &['a'], SymmetryKind::Rotation180, &mut rng);
+            let solution = layout.clone().solve(usize::MAX, ());
+
+            assert!(!solution.is_empty());
+
+            // The rotation of every solution is a solution as well.
+            for nonogram in &solution.collection {
+                let mut rotated = Nonogram::new(6, 5);
+
+                for row in 0..5 {
+                    for col in 0..6 {
+                        rotated[(5 - col, 4 - row)] = nonogram[(col, row)];
+                    }
+                }
+                assert!(solution.collection.contains(&rotated));
+            }
+            if let Ok(nonogram) = layout.solve_unique(()) {
+                assert!(is_symmetric(&nonogram, SymmetryKind::Rotation180));
+            }
+        }
+    }
+
+    #[test]
+    fn random_size() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let layout = random(4, 7, &['a', 'b'], &mut rng);
+
+        assert_eq!(4, layout.col_count());
+        assert_eq!(7, layout.row_count());
+        assert_eq!(layout.sum_cols(), layout.sum_rows());
+    }
+
+    #[test]
+    fn symmetric_random_no_colors() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let layout = symmetric_random::<char, _>(3, 3, &[], SymmetryKind::All4, &mut rng);
+
+        assert_eq!(0, layout.sum_cols() + layout.sum_rows());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::algo::grid::Grid;
+use crate::algo::PartCell;
+use crate::{Cell, Layout, PartialNonogram};
+
+/// The constraint which determined the cell of a [Hint].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HintReason {
+    /// The items of the column leave only one value for the cell.
+    Column,
+    /// The items of the row leave only one value for the cell.
+    Row,
+}
+
+/// A cell which can be deduced from the cells already known.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Hint<T> {
+    pub col: usize,
+    pub row: usize,
+    pub value: Cell<T>,
+    pub reason: HintReason,
+}
+
+/// Finds a cell which can be deduced from the layout and the cells already known.
+///
+/// Returns `None` if no cell can be deduced without guessing
+/// or if the known cells contradict the layout.
+///
+/// # Panics
+/// If the size of `partial` doesn't match the layout.
+pub fn hint<T: Copy + PartialEq + Send + Sync>(
+    layout: &Layout<T>,
+    partial: &PartialNonogram<T>,
+) -> Option<Hint<T>> {
+    assert_eq!(layout.cols.len(), partial.cols());
+    assert_eq!(layout.rows.len(), partial.rows());
+
+    let mut cols = Grid::build(layout.cols.clone(), partial.rows());
+    let mut rows = Grid::build(layout.rows.clone(), partial.cols());
+
+    for row in 0..partial.rows() {
+        for col in 0..partial.cols() {
+            cols.set(col, row, partial[(col, row)]).ok()?;
+            rows.set(row, col, partial[(col, row)]).ok()?;
+        }
+    }
+    cols.update().ok()?;
+
+    if let Some((col, row, value)) = find_new(partial, |col, row| cols.get(col, row)) {
+        return Some(Hint {
+            col,
+            row,
+            value,
+            reason: HintReason::Column,
+        });
+    }
+    rows.update().ok()?;
+
+    find_new(partial, |col, row| rows.get(row, col)).map(|(col, row, value)| Hint {
+        col,
+        row,
+        value,
+        reason: HintReason::Row,
+    })
+}
+
+/// Finds the first cell, row by row, which is known now but was unknown before.
+///
+/// Tuple: `(col, row, value)`
+fn find_new<T: Copy>(
+    partial: &PartialNonogram<T>,
+    get: impl Fn(usize, usize) -> PartCell<T>,
+) -> Option<(usize, usize, Cell<T>)> {
+    (0..partial.rows())
+        .flat_map(|row| (0..partial.cols()).map(move |col| (col, row)))
+        .filter(|pos| partial[*pos].is_empty())
+        .find_map(|(col, row)| {
+            let value = get(col, row).try_into().ok()?;
+
+            Some((col, row, value))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Item;
+
+    #[test]
+    fn hint_column() {
+        let cols = vec![vec![Item::new('a', 2)], Vec::new()];
+        let rows = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        let hint = hint(&layout, &PartialNonogram::new(2, 2)).unwrap();
+
+        assert_eq!(0, hint.col);
+        assert_eq!(0, hint.row);
+        assert_eq!(Cell::Box { color: 'a' }, hint.value);
+        assert_eq!(HintReason::Column, hint.reason);
+    }
+
+    #[test]
+    fn hint_row() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 2)], Vec::new()];
+        let mut partial = PartialNonogram::new(2, 2);
+        partial[(0, 0)] = PartCell::Box { color: 'a' };
+        partial[(0, 1)] = PartCell::Space;
+
+        let hint = hint(&Layout::new(cols, rows), &partial).unwrap();
+
+        assert_eq!((1, 0), (hint.col, hint.row));
+        assert_eq!(Cell::Box { color: 'a' }, hint.value);
+    }
+
+    #[test]
+    fn hint_ambiguous() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(None, hint(&layout, &PartialNonogram::new(2, 2)));
+    }
+
+    #[test]
+    fn hint_contradiction() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let mut partial = PartialNonogram::new(1, 1);
+        partial[(0, 0)] = PartCell::Space;
+
+        assert_eq!(None, hint(&Layout::new(cols, rows), &partial));
+    }
+
+    #[test]
+    fn hint_progress() {
+        let cols = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 2)], vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+        let mut partial = PartialNonogram::new(2, 2);
+
+        while let Some(hint) = hint(&layout, &partial) {
+            let known = partial.known_count();
+
+            partial[(hint.col, hint.row)] = hint.value.into();
+            assert_eq!(known + 1, partial.known_count());
+        }
+        assert_eq!(4, partial.known_count());
+        assert!(matches!(partial[(1, 1)], PartCell::Space));
+    }
+}
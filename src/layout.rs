@@ -1,14 +1,27 @@
-use crate::{Solution, Token};
+use crate::{
+    Cell, Difficulty, Nonogram, NonogramError, PartialError, PartialNonogram, PartialSolution,
+    Solution, SolutionCount, Status, Token,
+};
 
-use crate::algo::collection::Collection;
-use crate::algo::Branch;
+use crate::algo::collection::{Collection, Counter, ShardedCollection};
+use crate::algo::line::Line;
+use crate::algo::{Branch, Error, PartCell};
 
+use rayon::ThreadPoolBuilder;
+#[cfg(any(feature = "json", feature = "msgpack"))]
+use serde::de::DeserializeOwned;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+#[cfg(feature = "json")]
+use std::io::{Read, Write};
+use std::ops::Deref;
 
 /// An item in a number grid.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Default, Clone)]
+#[derive(Default, Clone, PartialEq, Debug)]
 pub struct Item<T> {
     pub color: T,
     pub len: usize,
@@ -21,14 +34,624 @@ impl<T> Item<T> {
     }
 }
 
+impl<T: PartialEq> Item<T> {
+    /// The minimum amount of cells between this item and the next one.
+    pub fn min_gap_to_next(&self, next: &Item<T>) -> usize {
+        min_gap(self.color == next.color)
+    }
+}
+
+/// The minimum amount of cells between two adjacent items.
+///
+/// Items of the same color require a space in between.
+pub fn min_gap(same_color: bool) -> usize {
+    if same_color {
+        1
+    } else {
+        0
+    }
+}
+
+/// The minimum amount of cells required to fit a sequence of items.
+///
+/// Adjacent items of the same color require a space in between.
+pub fn min_length<T: PartialEq>(items: &[Item<T>]) -> usize {
+    let mut items = items.iter().filter(|item| item.len > 0).peekable();
+    let mut len = 0;
+
+    while let Some(item) = items.next() {
+        len += item.len;
+
+        if let Some(next) = items.peek() {
+            len += item.min_gap_to_next(next);
+        }
+    }
+    len
+}
+
+/// A reason why a [Layout] cannot be solved.
+///
+/// `items` contains the items of the line and
+/// `min_len_detail` the minimum length of the items up to and including each item.
+#[derive(Clone, PartialEq, Debug)]
+pub enum LayoutError<T> {
+    /// The items of a column do not fit into the amount of rows.
+    ColTooLong {
+        index: usize,
+        min_len: usize,
+        line_len: usize,
+        items: Vec<Item<T>>,
+        min_len_detail: Vec<usize>,
+    },
+    /// The items of a row do not fit into the amount of columns.
+    RowTooLong {
+        index: usize,
+        min_len: usize,
+        line_len: usize,
+        items: Vec<Item<T>>,
+        min_len_detail: Vec<usize>,
+    },
+    /// The rows and columns describe a different amount of boxes.
+    SumMismatch { row_sum: usize, col_sum: usize },
+}
+
+impl<T> From<Vec<LayoutError<T>>> for NonogramError {
+    /// Discards the details returned by [Layout::validate].
+    fn from(_: Vec<LayoutError<T>>) -> Self {
+        NonogramError::InvalidLayout
+    }
+}
+
+/// Counts the cells of a line which are known from its items alone,
+/// no matter where the items are placed.
+fn overlap<T: PartialEq>(items: &[Item<T>], len: usize) -> usize {
+    let min_len = min_length(items);
+
+    if min_len == 0 || min_len >= len {
+        return len;
+    }
+    let slack = len - min_len;
+
+    items
+        .iter()
+        .map(|item| item.len.saturating_sub(slack))
+        .sum()
+}
+
+/// Returns the minimum length of the items up to and including each item.
+fn min_length_detail<T: PartialEq>(items: &[Item<T>]) -> Vec<usize> {
+    (1..=items.len())
+        .map(|end| min_length(&items[..end]))
+        .collect()
+}
+
+/// Formats the lengths of items like `3, 2, 3`.
+fn lengths<T>(items: &[Item<T>]) -> String {
+    items
+        .iter()
+        .map(|item| item.len.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A reason why [Layout::from_nonogram_multi] did not return a layout.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MultiLayoutError {
+    /// No nonograms were given.
+    Empty,
+    /// The nonograms have different sizes.
+    DimensionMismatch,
+    /// The nonograms result in different items for a column.
+    ColMismatch { index: usize },
+    /// The nonograms result in different items for a row.
+    RowMismatch { index: usize },
+}
+
+/// A reason why a [Layout] is not monochrome.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MonochromeError {
+    /// An item has a color other than `1`.
+    UnexpectedColor(usize),
+}
+
+/// A reason why [Layout::solve_unique] did not return a solution.
+#[derive(Clone, PartialEq, Debug)]
+pub enum UniquenessError {
+    /// The layout has no solution.
+    NoSolution,
+    /// The layout has more than one solution.
+    /// Contains the amount of solutions found before the search was stopped.
+    MultipleSolutions(usize),
+    /// The token cancelled the search before uniqueness could be decided.
+    Cancelled,
+}
+
 /// A layout composed of two number grids.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Default, Clone)]
+#[derive(Default, Clone, PartialEq, Debug)]
 pub struct Layout<T> {
     pub cols: Vec<Vec<Item<T>>>,
     pub rows: Vec<Vec<Item<T>>>,
 }
 
+impl<T> Layout<T> {
+    /// Creates a layout of the given size where every line has no items.
+    pub fn new_empty(cols: usize, rows: usize) -> Self {
+        Self {
+            cols: (0..cols).map(|_| Vec::new()).collect(),
+            rows: (0..rows).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    /// Appends an item to a column.
+    ///
+    /// # Panics
+    /// If the column is out of bounds.
+    pub fn add_col_item(&mut self, col: usize, item: Item<T>) {
+        self.cols[col].push(item);
+    }
+
+    /// Appends an item to a row.
+    ///
+    /// # Panics
+    /// If the row is out of bounds.
+    pub fn add_row_item(&mut self, row: usize, item: Item<T>) {
+        self.rows[row].push(item);
+    }
+
+    /// Returns the layout with an item appended to a column.
+    ///
+    /// See [Layout::add_col_item].
+    pub fn with_col_item(mut self, col: usize, item: Item<T>) -> Self {
+        self.add_col_item(col, item);
+        self
+    }
+
+    /// Returns the layout with an item appended to a row.
+    ///
+    /// See [Layout::add_row_item].
+    pub fn with_row_item(mut self, row: usize, item: Item<T>) -> Self {
+        self.add_row_item(row, item);
+        self
+    }
+
+    /// Converts the color of every item.
+    fn map_colors<U>(&self, f: impl Fn(&T) -> U) -> Layout<U> {
+        let map = |lines: &Vec<Vec<Item<T>>>| {
+            lines
+                .iter()
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|item| Item::new(f(&item.color), item.len))
+                        .collect()
+                })
+                .collect()
+        };
+        Layout {
+            cols: map(&self.cols),
+            rows: map(&self.rows),
+        }
+    }
+
+    /// Returns the column count.
+    pub fn col_count(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// Returns the row count.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the amount of items in all columns and rows.
+    pub fn item_count(&self) -> usize {
+        self.cols.iter().chain(&self.rows).map(Vec::len).sum()
+    }
+
+    /// Returns the largest amount of items in a single column or row.
+    pub fn max_items_per_line(&self) -> usize {
+        self.cols
+            .iter()
+            .chain(&self.rows)
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Removes all items of length zero, which don't affect the solution.
+    pub fn remove_redundant_zeros(&mut self) {
+        for items in self.cols.iter_mut().chain(self.rows.iter_mut()) {
+            items.retain(|item| item.len > 0);
+        }
+    }
+
+    /// Returns the layout without items of length zero.
+    ///
+    /// See [Layout::remove_redundant_zeros].
+    pub fn cleaned(mut self) -> Self {
+        self.remove_redundant_zeros();
+        self
+    }
+
+    /// Swaps the columns and rows.
+    pub fn transpose(self) -> Self {
+        Self {
+            cols: self.rows,
+            rows: self.cols,
+        }
+    }
+
+    /// Returns the total length of all column items.
+    pub fn sum_cols(&self) -> usize {
+        self.cols.iter().flatten().map(|item| item.len).sum()
+    }
+
+    /// Returns the total length of all row items.
+    pub fn sum_rows(&self) -> usize {
+        self.rows.iter().flatten().map(|item| item.len).sum()
+    }
+
+    /// Iterates over the items of each column.
+    pub fn col_clues(&self) -> impl ExactSizeIterator<Item = &[Item<T>]> {
+        self.cols.iter().map(Vec::as_slice)
+    }
+
+    /// Iterates over the items of each row.
+    pub fn row_clues(&self) -> impl ExactSizeIterator<Item = &[Item<T>]> {
+        self.rows.iter().map(Vec::as_slice)
+    }
+}
+
+impl<T: Debug> Layout<T> {
+    /// Returns an adapter which displays the items with one column or row per line.
+    ///
+    /// Each item is written as its length followed by its color, like `3 ('a')`.
+    /// Lines without items are written as `0`.
+    ///
+    /// ```text
+    /// col 1: 1 ('a') 2 ('b')
+    /// row 1: 0
+    /// ```
+    ///
+    /// This is an adapter rather than a [Display] impl, because `Layout<usize>` already displays
+    /// in the text format, see [Layout::to_text], which can be parsed again.
+    pub fn display_clues(&self) -> impl Display + '_ {
+        ClueDisplay(self)
+    }
+}
+
+/// Displays the items of a layout, see [Layout::display_clues].
+struct ClueDisplay<'a, T>(&'a Layout<T>);
+
+impl<T: Debug> Display for ClueDisplay<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let cols = self.0.cols.iter().enumerate().map(|line| ("col", line));
+        let rows = self.0.rows.iter().enumerate().map(|line| ("row", line));
+
+        for (i, (name, (index, items))) in cols.chain(rows).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{} {}: ", name, index + 1)?;
+            write_items(f, items, |f, item| {
+                write!(f, "{} ({:?})", item.len, item.color)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the items of a line separated by spaces, or `0` if the line has no items.
+pub(crate) fn write_items<T>(
+    f: &mut Formatter<'_>,
+    items: &[Item<T>],
+    item: impl Fn(&mut Formatter<'_>, &Item<T>) -> fmt::Result,
+) -> fmt::Result {
+    if items.is_empty() {
+        return write!(f, "0");
+    }
+    for (index, value) in items.iter().enumerate() {
+        if index > 0 {
+            write!(f, " ")?;
+        }
+        item(f, value)?;
+    }
+    Ok(())
+}
+
+impl<T: PartialEq> Layout<T> {
+    /// The minimum amount of cells required to fit the items of a column.
+    ///
+    /// # Panics
+    /// If the column is out of bounds.
+    pub fn min_col_length(&self, col: usize) -> usize {
+        min_length(&self.cols[col])
+    }
+
+    /// The minimum amount of cells required to fit the items of a row.
+    ///
+    /// # Panics
+    /// If the row is out of bounds.
+    pub fn min_row_length(&self, row: usize) -> usize {
+        min_length(&self.rows[row])
+    }
+}
+
+impl<T: Clone + PartialEq> Layout<T> {
+    /// Checks the layout for obvious errors without solving it.
+    ///
+    /// Returns all errors found.
+    pub fn validate(&self) -> Result<(), Vec<LayoutError<T>>> {
+        let mut errors = Vec::new();
+
+        for (index, items) in self.cols.iter().enumerate() {
+            let min_len = min_length(items);
+            let line_len = self.rows.len();
+
+            if min_len > line_len {
+                errors.push(LayoutError::ColTooLong {
+                    index,
+                    min_len,
+                    line_len,
+                    items: items.clone(),
+                    min_len_detail: min_length_detail(items),
+                });
+            }
+        }
+        for (index, items) in self.rows.iter().enumerate() {
+            let min_len = min_length(items);
+            let line_len = self.cols.len();
+
+            if min_len > line_len {
+                errors.push(LayoutError::RowTooLong {
+                    index,
+                    min_len,
+                    line_len,
+                    items: items.clone(),
+                    min_len_detail: min_length_detail(items),
+                });
+            }
+        }
+        let row_sum = self.sum_rows();
+        let col_sum = self.sum_cols();
+
+        if row_sum != col_sum {
+            errors.push(LayoutError::SumMismatch { row_sum, col_sum });
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Explains why the layout is invalid, with one line per [LayoutError].
+    ///
+    /// Columns and rows are numbered starting at 1.
+    /// Returns an empty string if [Layout::validate] finds no errors.
+    pub fn diagnose(&self) -> String {
+        let errors = self.validate().err().unwrap_or_default();
+
+        errors
+            .into_iter()
+            .map(|error| match error {
+                LayoutError::ColTooLong {
+                    index,
+                    min_len,
+                    line_len,
+                    items,
+                    ..
+                } => format!(
+                    "Column {}, items [{}] require at least {} cells but the grid has {} rows.",
+                    index + 1,
+                    lengths(&items),
+                    min_len,
+                    line_len
+                ),
+                LayoutError::RowTooLong {
+                    index,
+                    min_len,
+                    line_len,
+                    items,
+                    ..
+                } => format!(
+                    "Row {}, items [{}] require at least {} cells but the grid has {} columns.",
+                    index + 1,
+                    lengths(&items),
+                    min_len,
+                    line_len
+                ),
+                LayoutError::SumMismatch { row_sum, col_sum } => format!(
+                    "The rows contain {} boxes but the columns contain {}.",
+                    row_sum, col_sum
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T: Copy + PartialEq> Layout<T> {
+    /// Derives the layout which all given nonograms are a solution of.
+    ///
+    /// Fails if there are no nonograms or if they don't result in the same layout.
+    pub fn from_nonogram_multi(nonograms: &[Nonogram<T>]) -> Result<Self, MultiLayoutError> {
+        let (first, others) = nonograms.split_first().ok_or(MultiLayoutError::Empty)?;
+        let layout = Layout::from(first);
+
+        for nonogram in others {
+            if (nonogram.cols(), nonogram.rows()) != (first.cols(), first.rows()) {
+                return Err(MultiLayoutError::DimensionMismatch);
+            }
+            let other = Layout::from(nonogram);
+            let mismatch =
+                |a: &[Vec<Item<T>>], b: &[Vec<Item<T>>]| a.iter().zip(b).position(|(a, b)| a != b);
+            if let Some(index) = mismatch(&layout.cols, &other.cols) {
+                return Err(MultiLayoutError::ColMismatch { index });
+            }
+            if let Some(index) = mismatch(&layout.rows, &other.rows) {
+                return Err(MultiLayoutError::RowMismatch { index });
+            }
+        }
+        Ok(layout)
+    }
+
+    /// Derives the clues of the cells which are not known yet.
+    ///
+    /// Every line is updated by the line solver with the known cells of `partial`,
+    /// so known spaces split the line and fix the position of items together with known boxes.
+    /// The known boxes are then removed from the items with a fixed position:
+    /// such an item is shortened, split into its unknown parts or dropped if all of its cells are known.
+    /// Items whose position isn't fixed keep their length, as do all items of a line contradicting `partial`.
+    /// Items of length zero are dropped.
+    ///
+    /// If every known box belongs to an item with a fixed position,
+    /// each solution matching `partial` still solves the returned layout once its known cells are spaces.
+    ///
+    /// # Panics
+    /// If the size of `partial` doesn't match the layout.
+    pub fn apply_partial(&self, partial: &PartialNonogram<T>) -> Layout<T> {
+        assert_eq!(self.cols.len(), partial.cols());
+        assert_eq!(self.rows.len(), partial.rows());
+
+        Layout {
+            cols: (0..partial.cols())
+                .map(|col| {
+                    let cells: Vec<_> =
+                        (0..partial.rows()).map(|row| partial[(col, row)]).collect();
+
+                    reduce_line(&self.cols[col], &cells)
+                })
+                .collect(),
+            rows: (0..partial.rows())
+                .map(|row| {
+                    let cells: Vec<_> =
+                        (0..partial.cols()).map(|col| partial[(col, row)]).collect();
+
+                    reduce_line(&self.rows[row], &cells)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> From<&Nonogram<T>> for Layout<T> {
+    /// Derives the layout a nonogram is a solution of.
+    fn from(nonogram: &Nonogram<T>) -> Self {
+        Self {
+            cols: (0..nonogram.cols())
+                .map(|col| items_of((0..nonogram.rows()).map(|row| nonogram[(col, row)])))
+                .collect(),
+            rows: (0..nonogram.rows())
+                .map(|row| items_of((0..nonogram.cols()).map(|col| nonogram[(col, row)])))
+                .collect(),
+        }
+    }
+}
+
+/// Collects the items formed by the boxes of a sequence of cells.
+fn items_of<T: Copy + PartialEq>(cells: impl Iterator<Item = Cell<T>>) -> Vec<Item<T>> {
+    let mut items: Vec<Item<T>> = Vec::new();
+    let mut prev = Cell::Space;
+
+    for cell in cells {
+        if let Cell::Box { color } = cell {
+            match items.last_mut() {
+                Some(item) if prev == cell => item.len += 1,
+                _ => items.push(Item::new(color, 1)),
+            }
+        }
+        prev = cell;
+    }
+    items
+}
+
+/// Removes the known boxes from the items of a line whose position is fixed by the known cells.
+fn reduce_line<T: Copy + PartialEq>(items: &[Item<T>], cells: &[PartCell<T>]) -> Vec<Item<T>> {
+    let mut line = Line::build(items.to_vec(), cells.len());
+
+    let updated = cells
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, cell)| line.set(index, *cell))
+        .and_then(|_| loop {
+            let known = line.count_known();
+            line.update()?;
+
+            if line.count_known() == known {
+                return Ok(());
+            }
+        });
+    let items = items.iter().filter(|item| item.len > 0);
+
+    if updated.is_err() {
+        return items.cloned().collect();
+    }
+    let mut reduced = Vec::new();
+
+    for (item, chain) in items.zip(line.chains()) {
+        if !chain.solved() {
+            reduced.push(item.clone());
+            continue;
+        }
+        let mut len = 0;
+
+        for cell in &cells[chain.start()..chain.end()] {
+            if cell.is_known() {
+                if len > 0 {
+                    reduced.push(Item::new(item.color, len));
+                }
+                len = 0;
+            } else {
+                len += 1;
+            }
+        }
+        if len > 0 {
+            reduced.push(Item::new(item.color, len));
+        }
+    }
+    reduced
+}
+
+impl Layout<usize> {
+    /// Converts a monochrome layout, where every item has the color `1`, to a `Layout<bool>`.
+    ///
+    /// Fails with [MonochromeError::UnexpectedColor] if any item has another color.
+    pub fn to_bool_layout(&self) -> Result<Layout<bool>, MonochromeError> {
+        let mut items = self.cols.iter().chain(&self.rows).flatten();
+
+        match items.find(|item| item.color != 1) {
+            Some(item) => Err(MonochromeError::UnexpectedColor(item.color)),
+            None => Ok(self.map_colors(|_| true)),
+        }
+    }
+}
+
+impl Layout<bool> {
+    /// Converts a layout to a `Layout<usize>` where `true` becomes `1` and `false` becomes `0`.
+    pub fn to_usize_layout(&self) -> Layout<usize> {
+        self.map_colors(|color| usize::from(*color))
+    }
+}
+
+impl<T: Copy + Eq + Hash> Layout<T> {
+    /// Returns all colors used by the items.
+    ///
+    /// The colors are ordered by their first occurrence, columns first.
+    pub fn colors_used(&self) -> Vec<T> {
+        let mut seen = HashSet::new();
+
+        self.cols
+            .iter()
+            .chain(self.rows.iter())
+            .flatten()
+            .map(|item| item.color)
+            .filter(|color| seen.insert(*color))
+            .collect()
+    }
+}
+
 impl<T: Copy + PartialEq + Send + Sync> Layout<T> {
     /// Creates a new layout.
     pub fn new(cols: Vec<Vec<Item<T>>>, rows: Vec<Vec<Item<T>>>) -> Self {
@@ -37,28 +660,1193 @@ impl<T: Copy + PartialEq + Send + Sync> Layout<T> {
 
     /// Tries to solve a layout.
     ///
+    /// The solution is only [Status::Full] if the layout has more than `limit` solutions,
+    /// so finding exactly `limit` solutions still results in [Status::Complete].
+    ///
     /// # Parameters
     /// * `limit`: The maximum amount of nonograms to include in the solution.
     /// * `token`: Some cancellation token.
     pub fn solve(self, limit: usize, token: impl Token) -> Solution<T> {
-        let mut collection = Collection::new(limit, token);
+        let collection = Collection::new(limit, token);
+
+        Branch::build(self.cols, self.rows).solve(&collection);
+
+        collection.into()
+    }
+
+    /// Tries to solve a layout like [Layout::solve],
+    /// but skips all branches which require more than `max_depth` nested guesses.
+    ///
+    /// If any branch has been skipped, the solution is [Status::DepthExceeded].
+    ///
+    /// # Parameters
+    /// * `limit`: The maximum amount of nonograms to include in the solution.
+    /// * `max_depth`: The maximum amount of nested guesses.
+    /// * `token`: Some cancellation token.
+    pub fn solve_with_max_depth(
+        self,
+        limit: usize,
+        max_depth: usize,
+        token: impl Token,
+    ) -> Solution<T> {
+        let collection = Collection::new(limit, token).limit_depth(max_depth);
+
+        Branch::build(self.cols, self.rows).solve(&collection);
+
+        collection.into()
+    }
+
+    /// Counts the solutions without storing them.
+    ///
+    /// # Parameters
+    /// * `limit`: The maximum amount of solutions to count.
+    /// * `token`: Some cancellation token.
+    pub fn count_solutions(self, limit: usize, token: impl Token) -> SolutionCount {
+        let counter = Counter::new(limit, token);
+
+        Branch::build(self.cols, self.rows).solve(&counter);
+
+        counter.into()
+    }
+
+    /// Tries to solve a layout where some cells are already known.
+    ///
+    /// Hints contradicting the layout result in an empty solution.
+    ///
+    /// # Parameters
+    /// * `hints`: The known cells.
+    /// * `limit`: The maximum amount of nonograms to include in the solution.
+    /// * `token`: Some cancellation token.
+    ///
+    /// # Panics
+    /// If the size of `hints` doesn't match the layout.
+    pub fn solve_with_hints(
+        self,
+        hints: &PartialNonogram<T>,
+        limit: usize,
+        token: impl Token,
+    ) -> Solution<T> {
+        assert_eq!(self.cols.len(), hints.cols());
+        assert_eq!(self.rows.len(), hints.rows());
+
+        let collection = Collection::new(limit, token);
+        let mut branch = Branch::build(self.cols, self.rows);
+        let valid = (0..hints.rows())
+            .flat_map(|row| (0..hints.cols()).map(move |col| (col, row)))
+            .all(|(col, row)| branch.hint(col, row, hints[(col, row)]).is_ok());
+
+        if valid {
+            branch.solve(&collection);
+        }
+        collection.into()
+    }
+
+    /// Finds the cells which differ between the solutions.
+    ///
+    /// Returns `(col, row)` positions, row by row.
+    /// If there is at most one solution, no cell is ambiguous.
+    ///
+    /// # Parameters
+    /// * `limit`: The maximum amount of solutions to compare.
+    /// * `token`: Some cancellation token.
+    pub fn ambiguous_cells(self, limit: usize, token: impl Token) -> Vec<(usize, usize)> {
+        let solution = self.solve(limit, token);
+        let mut cells = Vec::new();
+
+        if let Some((first, others)) = solution.collection.split_first() {
+            for row in 0..first.rows() {
+                for col in 0..first.cols() {
+                    if others
+                        .iter()
+                        .any(|other| other[(col, row)] != first[(col, row)])
+                    {
+                        cells.push((col, row));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    /// Solves a layout and returns the solution only if it is unique.
+    ///
+    /// # Parameters
+    /// * `token`: Some cancellation token.
+    pub fn solve_unique(self, token: impl Token) -> Result<Nonogram<T>, UniquenessError> {
+        let mut solution = self.solve(1, token);
+
+        match solution.status {
+            Status::Cancelled | Status::DepthExceeded => Err(UniquenessError::Cancelled),
+            // The solution beyond the limit has been dropped.
+            Status::Full => Err(UniquenessError::MultipleSolutions(solution.len() + 1)),
+            Status::Complete => solution.collection.pop().ok_or(UniquenessError::NoSolution),
+        }
+    }
+
+    /// Deduces as many cells as possible without guessing.
+    ///
+    /// # Parameters
+    /// * `token`: Some cancellation token.
+    pub fn solve_partial(self, token: impl Token) -> Result<PartialSolution<T>, PartialError> {
+        match Branch::build(self.cols, self.rows).propagate(&token) {
+            Ok(cells) => Ok(PartialSolution::new(cells)),
+            Err(Error::Cancelled) => Err(PartialError::Cancelled),
+            Err(_) => Err(PartialError::Invalid),
+        }
+    }
+
+    /// Checks if the layout can be solved without guessing.
+    ///
+    /// This runs a single branch of [Layout::solve_partial] on a copy of the layout.
+    pub fn is_logic_solvable(&self) -> bool {
+        matches!(self.clone().solve_partial(()), Ok(partial) if partial.is_complete)
+    }
+
+    /// Solves a layout which has already been validated.
+    ///
+    /// # Parameters
+    /// * `layout`: The validated layout.
+    /// * `limit`: The maximum amount of nonograms to include in the solution.
+    /// * `token`: Some cancellation token.
+    pub fn solve_validated(
+        layout: ValidatedLayout<T>,
+        limit: usize,
+        token: impl Token,
+    ) -> Solution<T> {
+        layout.0.solve(limit, token)
+    }
+
+    /// Tries to solve a layout like [Layout::solve],
+    /// but stores the solutions in one collection per worker thread.
+    ///
+    /// This reduces lock contention on machines with many cores.
+    ///
+    /// # Parameters
+    /// * `limit`: The maximum amount of nonograms to include in the solution.
+    /// * `token`: Some cancellation token.
+    pub fn solve_sharded(self, limit: usize, token: impl Token) -> Solution<T> {
+        let collection = ShardedCollection::new(limit, token);
 
-        Branch::build(self.cols, self.rows).solve(&mut collection);
+        Branch::build(self.cols, self.rows).solve(&collection);
 
         collection.into()
     }
+
+    /// Estimates how hard the layout is to solve by hand.
+    ///
+    /// The estimate only runs the line propagation of [Layout::solve_partial]
+    /// and never guesses, so it doesn't prove that a solution is unique.
+    /// See [Difficulty] for the thresholds of each rating.
+    pub fn estimate_difficulty(&self) -> Difficulty {
+        let (cols, rows) = (self.cols.len(), self.rows.len());
+        let overlaps: Vec<(usize, usize)> = self
+            .cols
+            .iter()
+            .map(|items| (overlap(items, rows), rows))
+            .chain(self.rows.iter().map(|items| (overlap(items, cols), cols)))
+            .collect();
+
+        let partial = match self.clone().solve_partial(()) {
+            Ok(partial) => partial,
+            Err(_) => return Difficulty::Expert,
+        };
+        if partial.is_complete {
+            return if overlaps.iter().all(|(overlap, len)| overlap == len) {
+                Difficulty::Trivial
+            } else {
+                Difficulty::Easy
+            };
+        }
+        if partial.forced_count * 2 >= cols * rows {
+            Difficulty::Medium
+        } else if overlaps.iter().any(|(overlap, _)| *overlap > 0) {
+            Difficulty::Hard
+        } else {
+            Difficulty::Expert
+        }
+    }
+}
+
+/// Counts all solutions of a layout on a dedicated thread pool with the given amount of threads.
+///
+/// A thread count of zero uses the default of rayon.
+/// If the token cancels the search, the solutions found so far are counted.
+///
+/// # Panics
+/// If the thread pool can't be created.
+pub fn solve_parallel_count<T: Copy + PartialEq + Send + Sync>(
+    layout: Layout<T>,
+    threads: usize,
+    token: impl Token,
+) -> usize {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("failed to create the thread pool");
+
+    pool.install(|| layout.count_solutions(usize::MAX, token).count)
+}
+
+#[cfg(feature = "json")]
+impl<T: DeserializeOwned> Layout<T> {
+    /// Reads a layout as JSON without buffering the whole input first.
+    pub fn from_json_reader<R: Read>(reader: R) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: Serialize> Layout<T> {
+    /// Writes the layout as JSON.
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(writer, self)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: DeserializeOwned> Layout<T> {
+    /// Reads a layout from MessagePack, see [Layout::to_msgpack].
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(bytes)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: Serialize> Layout<T> {
+    /// Writes the layout as MessagePack.
+    ///
+    /// The schema is the same as for JSON, with structs encoded as maps.
+    /// The layout starts with `0x82` (a map with two entries)
+    /// followed by `0xa4` and `cols` (a string of four bytes).
+    ///
+    /// # Panics
+    /// If the colors can't be serialized.
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(self).expect("failed to serialize the layout")
+    }
+}
+
+/// A [Layout] which passed [Layout::validate].
+///
+/// The layout can be read through [Deref], but not modified.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ValidatedLayout<T>(Layout<T>);
+
+impl<T: Clone + PartialEq> ValidatedLayout<T> {
+    /// Validates a layout.
+    ///
+    /// Returns all errors found, if the layout is invalid.
+    pub fn new(layout: Layout<T>) -> Result<Self, Vec<LayoutError<T>>> {
+        layout.validate()?;
+
+        Ok(Self(layout))
+    }
+}
+
+impl<T> ValidatedLayout<T> {
+    /// Returns the inner layout.
+    pub fn into_inner(self) -> Layout<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidatedLayout<T> {
+    type Target = Layout<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algo::PartCell;
+    use crate::cancel::Cancel;
 
     #[test]
-    fn layout_solve() {
-        let cols = vec![vec![Item::new('a', 1)]];
-        let rows = vec![vec![Item::new('a', 1)]];
-        let layout = Layout::new(cols, rows);
+    fn item_min_gap_to_next_same_color() {
+        assert_eq!(1, Item::new('a', 3).min_gap_to_next(&Item::new('a', 1)));
+    }
 
-        assert_eq!(1, layout.solve(usize::MAX, ()).collection.len());
+    #[test]
+    fn item_min_gap_to_next_different_color() {
+        assert_eq!(0, Item::new('a', 3).min_gap_to_next(&Item::new('b', 1)));
+    }
+
+    #[test]
+    fn min_length_empty() {
+        assert_eq!(0, min_length::<char>(&[]));
+    }
+
+    #[test]
+    fn min_length_single() {
+        assert_eq!(3, min_length(&[Item::new('a', 3)]));
+    }
+
+    #[test]
+    fn min_length_same_color() {
+        assert_eq!(6, min_length(&[Item::new('a', 3), Item::new('a', 2)]));
+    }
+
+    #[test]
+    fn min_length_different_color() {
+        assert_eq!(5, min_length(&[Item::new('a', 3), Item::new('b', 2)]));
+    }
+
+    #[test]
+    fn min_length_zeros() {
+        let items = [Item::new('a', 1), Item::new('b', 0), Item::new('a', 1)];
+
+        assert_eq!(3, min_length(&items));
+    }
+
+    #[test]
+    fn layout_min_col_length() {
+        let cols = vec![vec![Item::new('a', 1), Item::new('a', 1)]];
+        let layout = Layout::new(cols, Vec::new());
+
+        assert_eq!(3, layout.min_col_length(0));
+    }
+
+    #[test]
+    fn layout_min_row_length() {
+        let rows = vec![vec![Item::new('a', 1), Item::new('b', 1)]];
+        let layout = Layout::new(Vec::new(), rows);
+
+        assert_eq!(2, layout.min_row_length(0));
+    }
+
+    #[test]
+    fn layout_validate() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1), Item::new('b', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(Ok(()), layout.validate());
+    }
+
+    #[test]
+    fn layout_validate_too_long() {
+        let cols = vec![vec![Item::new('a', 2)], vec![]];
+        let rows = vec![vec![Item::new('a', 1), Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        let errors = layout.validate().unwrap_err();
+
+        assert_eq!(
+            vec![
+                LayoutError::ColTooLong {
+                    index: 0,
+                    min_len: 2,
+                    line_len: 1,
+                    items: vec![Item::new('a', 2)],
+                    min_len_detail: vec![2],
+                },
+                LayoutError::RowTooLong {
+                    index: 0,
+                    min_len: 3,
+                    line_len: 2,
+                    items: vec![Item::new('a', 1), Item::new('a', 1)],
+                    min_len_detail: vec![1, 3],
+                }
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn layout_new_empty() {
+        let layout = Layout::<char>::new_empty(3, 2);
+
+        assert_eq!(3, layout.cols.len());
+        assert_eq!(2, layout.rows.len());
+        assert!(layout.cols.iter().chain(&layout.rows).all(Vec::is_empty));
+    }
+
+    #[test]
+    fn layout_default_solve() {
+        let layout: Layout<char> = Layout::default();
+
+        assert_eq!(0, layout.col_count());
+        assert_eq!(0, layout.row_count());
+
+        let solution = layout.solve(usize::MAX, ());
+
+        assert_eq!(1, solution.len());
+        assert_eq!(0, solution.collection[0].cols());
+    }
+
+    #[test]
+    fn layout_new_empty_solve() {
+        let solution = Layout::<char>::new_empty(3, 2).solve(2, ());
+
+        assert_eq!(1, solution.len());
+        assert_eq!(0, solution.first().unwrap().box_count());
+    }
+
+    #[test]
+    fn layout_bool_round_trip() {
+        let layout: Layout<usize> = Layout::new(
+            vec![
+                vec![Item::new(1, 2)],
+                vec![Item::new(1, 1), Item::new(1, 1)],
+            ],
+            vec![vec![Item::new(1, 2)], vec![], vec![Item::new(1, 1)]],
+        );
+        let bools = layout.to_bool_layout().unwrap();
+
+        assert_eq!(vec![Item::new(true, 2)], bools.cols[0]);
+        assert_eq!(layout, bools.to_usize_layout());
+    }
+
+    #[test]
+    fn layout_bool_round_trip_parsed() {
+        let layout: Layout<usize> = "cols: 1 2 | 3\nrows: 2 | 1 3".parse().unwrap();
+        let bools = layout.to_bool_layout().unwrap();
+
+        assert_eq!(vec![Item::new(true, 1), Item::new(true, 2)], bools.cols[0]);
+        assert_eq!(layout, bools.to_usize_layout());
+    }
+
+    #[test]
+    fn layout_to_bool_layout_unexpected_color() {
+        let layout: Layout<usize> = Layout::new(
+            vec![vec![Item::new(1, 1)]],
+            vec![vec![Item::new(1, 1), Item::new(2, 1)]],
+        );
+
+        assert_eq!(
+            Err(MonochromeError::UnexpectedColor(2)),
+            layout.to_bool_layout()
+        );
+    }
+
+    #[test]
+    fn layout_to_usize_layout() {
+        let layout = Layout::new(vec![vec![Item::new(true, 1), Item::new(false, 2)]], vec![]);
+
+        assert_eq!(
+            vec![Item::new(1, 1), Item::new(0, 2)],
+            layout.to_usize_layout().cols[0]
+        );
+    }
+
+    #[test]
+    fn layout_item_count() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 1), Item::new('b', 1)], vec![]],
+            vec![vec![Item::new('a', 1)]; 2],
+        );
+
+        assert_eq!(4, layout.item_count());
+        assert_eq!(2, layout.max_items_per_line());
+    }
+
+    #[test]
+    fn layout_item_count_empty() {
+        let layout = Layout::<char>::new_empty(3, 2);
+
+        assert_eq!(0, layout.item_count());
+        assert_eq!(0, layout.max_items_per_line());
+        assert_eq!(0, Layout::<char>::default().max_items_per_line());
+    }
+
+    #[test]
+    fn layout_from_nonogram_multi() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 1)]; 2],
+            vec![vec![Item::new('a', 1)]; 2],
+        );
+        let solutions = layout.clone().solve(usize::MAX, ()).collection;
+
+        assert_eq!(Ok(layout), Layout::from_nonogram_multi(&solutions));
+    }
+
+    #[test]
+    fn layout_from_nonogram_multi_mismatch() {
+        let a = Nonogram::from_rows(vec![vec![Cell::Box { color: 'a' }, Cell::Space]]).unwrap();
+        let b = Nonogram::from_rows(vec![vec![Cell::Space, Cell::Box { color: 'a' }]]).unwrap();
+        let c = Nonogram::from_rows(vec![vec![Cell::Box { color: 'b' }, Cell::Space]]).unwrap();
+
+        assert_eq!(
+            Err(MultiLayoutError::ColMismatch { index: 0 }),
+            Layout::from_nonogram_multi(&[a.clone(), b])
+        );
+        assert_eq!(
+            Err(MultiLayoutError::ColMismatch { index: 0 }),
+            Layout::from_nonogram_multi(&[a.clone(), c])
+        );
+        assert_eq!(
+            Err(MultiLayoutError::DimensionMismatch),
+            Layout::from_nonogram_multi(&[a, Nonogram::new(1, 1)])
+        );
+        assert_eq!(
+            Err(MultiLayoutError::Empty),
+            Layout::<char>::from_nonogram_multi(&[])
+        );
+    }
+
+    #[test]
+    fn layout_from_nonogram_multi_row_mismatch() {
+        let a = Nonogram::from_rows(vec![
+            vec![Cell::Box { color: 'a' }, Cell::Box { color: 'a' }],
+            vec![Cell::Space, Cell::Space],
+            vec![Cell::Space, Cell::Space],
+        ])
+        .unwrap();
+        let b = Nonogram::from_rows(vec![
+            vec![Cell::Box { color: 'a' }, Cell::Space],
+            vec![Cell::Space, Cell::Space],
+            vec![Cell::Space, Cell::Box { color: 'a' }],
+        ])
+        .unwrap();
+
+        assert_eq!(
+            Err(MultiLayoutError::RowMismatch { index: 0 }),
+            Layout::from_nonogram_multi(&[a, b])
+        );
+    }
+
+    #[test]
+    fn layout_debug() {
+        let layout = Layout::new(vec![vec![Item::new('a', 1)]], vec![vec![Item::new('a', 1)]]);
+
+        assert!(format!("{:?}", layout).contains("cols"));
+    }
+
+    #[test]
+    fn layout_display_clues() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 1), Item::new('b', 2)], vec![]],
+            vec![vec![Item::new('a', 1)], vec![Item::new('b', 2)], vec![]],
+        );
+        let text = layout.display_clues().to_string();
+
+        assert_eq!(5, text.lines().count());
+        assert_eq!(
+            "col 1: 1 ('a') 2 ('b')\ncol 2: 0\nrow 1: 1 ('a')\nrow 2: 2 ('b')\nrow 3: 0",
+            text
+        );
+    }
+
+    #[test]
+    fn layout_with_items() {
+        let layout = Layout::new_empty(2, 1)
+            .with_col_item(0, Item::new('a', 1))
+            .with_row_item(0, Item::new('a', 1))
+            .with_row_item(0, Item::new('b', 1))
+            .with_col_item(1, Item::new('b', 1));
+
+        assert_eq!(
+            vec![vec![Item::new('a', 1)], vec![Item::new('b', 1)]],
+            layout.cols
+        );
+        assert_eq!(
+            vec![vec![Item::new('a', 1), Item::new('b', 1)]],
+            layout.rows
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn layout_add_col_item_oob() {
+        Layout::new_empty(2, 2).add_col_item(2, Item::new('a', 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn layout_add_row_item_oob() {
+        Layout::new_empty(2, 2).add_row_item(2, Item::new('a', 1));
+    }
+
+    #[test]
+    fn layout_estimate_difficulty_trivial() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 2)]; 2],
+            vec![vec![Item::new('a', 2)]; 2],
+        );
+
+        assert_eq!(Difficulty::Trivial, layout.estimate_difficulty());
+    }
+
+    #[test]
+    fn layout_estimate_difficulty_easy() {
+        let cols = vec![
+            vec![Item::new('a', 3)],
+            vec![Item::new('a', 1)],
+            vec![Item::new('a', 1)],
+        ];
+        let rows = cols.clone();
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(Difficulty::Easy, layout.estimate_difficulty());
+    }
+
+    #[test]
+    fn layout_estimate_difficulty_ambiguous() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 1)]; 20],
+            vec![vec![Item::new('a', 1)]; 20],
+        );
+
+        assert!(layout.estimate_difficulty() >= Difficulty::Hard);
+    }
+
+    #[test]
+    fn layout_estimate_difficulty_medium() {
+        // The top left 2x2 block is ambiguous, all other cells are forced.
+        let mut cols = vec![vec![Item::new('a', 1), Item::new('a', 2)]; 2];
+        cols.extend(vec![vec![Item::new('a', 2)]; 3]);
+        let mut rows = vec![vec![Item::new('a', 1)]; 2];
+        rows.push(Vec::new());
+        rows.extend(vec![vec![Item::new('a', 5)]; 2]);
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(Difficulty::Medium, layout.estimate_difficulty());
+    }
+
+    #[test]
+    fn layout_estimate_difficulty_hard() {
+        let mut cols = vec![vec![Item::new('a', 1)]; 9];
+        cols.push(Vec::new());
+        cols.push(vec![Item::new('a', 9)]);
+        let rows = vec![vec![Item::new('a', 1), Item::new('a', 1)]; 9];
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(Difficulty::Hard, layout.estimate_difficulty());
+    }
+
+    #[test]
+    fn overlap_of_items() {
+        assert_eq!(5, overlap::<char>(&[], 5));
+        assert_eq!(5, overlap(&[Item::new('a', 2), Item::new('a', 2)], 5));
+        assert_eq!(1, overlap(&[Item::new('a', 3)], 5));
+        assert_eq!(0, overlap(&[Item::new('a', 1), Item::new('a', 2)], 6));
+        assert_eq!(3, overlap(&[Item::new('a', 2), Item::new('b', 3)], 6));
+    }
+
+    #[test]
+    fn layout_estimate_difficulty_invalid() {
+        let layout = Layout::new(vec![vec![Item::new('a', 2)]], vec![vec![Item::new('a', 1)]]);
+
+        assert_eq!(Difficulty::Expert, layout.estimate_difficulty());
+    }
+
+    #[test]
+    fn layout_col_count_row_count() {
+        let cols = vec![vec![Item::new('a', 1)], Vec::new(), Vec::new()];
+        let rows = vec![vec![Item::new('a', 1)], Vec::new()];
+        let layout = Layout::new(cols.clone(), rows.clone());
+
+        assert_eq!(cols.len(), layout.col_count());
+        assert_eq!(rows.len(), layout.row_count());
+    }
+
+    fn partial_layout() -> (Layout<char>, Nonogram<char>) {
+        let b = Cell::Box { color: 'a' };
+        let s = Cell::Space;
+        let nonogram = Nonogram::try_from(vec![
+            vec![b, s, b, b, s],
+            vec![b, s, s, b, b],
+            vec![s, s, b, s, s],
+        ])
+        .unwrap();
+        let cols = vec![
+            vec![Item::new('a', 2)],
+            Vec::new(),
+            vec![Item::new('a', 1), Item::new('a', 1)],
+            vec![Item::new('a', 2)],
+            vec![Item::new('a', 1)],
+        ];
+        let rows = vec![
+            vec![Item::new('a', 1), Item::new('a', 2)],
+            vec![Item::new('a', 1), Item::new('a', 2)],
+            vec![Item::new('a', 1)],
+        ];
+        (Layout::new(cols, rows), nonogram)
+    }
+
+    #[test]
+    fn layout_apply_partial() {
+        let (layout, nonogram) = partial_layout();
+        let mut partial = PartialNonogram::new(5, 3);
+        let mut residual = nonogram.clone();
+
+        for (col, row) in [
+            (1, 0),
+            (2, 0),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (0, 2),
+            (3, 2),
+            (4, 2),
+        ] {
+            partial[(col, row)] = nonogram[(col, row)].into();
+            residual[(col, row)] = Cell::Space;
+        }
+        let reduced = layout.apply_partial(&partial);
+
+        let cols = vec![
+            vec![Item::new('a', 2)],
+            Vec::new(),
+            vec![Item::new('a', 1)],
+            vec![Item::new('a', 1)],
+            vec![Item::new('a', 1)],
+        ];
+        let rows = vec![
+            vec![Item::new('a', 1), Item::new('a', 1)],
+            vec![Item::new('a', 1), Item::new('a', 1)],
+            vec![Item::new('a', 1)],
+        ];
+        assert_eq!(Layout::new(cols, rows), reduced);
+        assert_eq!(Layout::from(&residual), reduced);
+    }
+
+    #[test]
+    fn layout_apply_partial_unknown() {
+        let (layout, _) = partial_layout();
+
+        assert_eq!(layout, layout.apply_partial(&PartialNonogram::new(5, 3)));
+    }
+
+    #[test]
+    fn layout_apply_partial_split() {
+        let layout = Layout::new(
+            vec![vec![Item::new('a', 1)]; 5],
+            vec![vec![Item::new('a', 5)]],
+        );
+        let mut partial = PartialNonogram::new(5, 1);
+        partial[(2, 0)] = PartCell::Box { color: 'a' };
+
+        let reduced = layout.apply_partial(&partial);
+
+        assert_eq!(vec![Item::new('a', 2), Item::new('a', 2)], reduced.rows[0]);
+        assert!(reduced.cols[2].is_empty());
+        assert_eq!(vec![Item::new('a', 1)], reduced.cols[3]);
+    }
+
+    #[test]
+    fn layout_apply_partial_contradiction() {
+        let layout = Layout::new(vec![vec![Item::new('a', 1)]], vec![vec![Item::new('a', 1)]]);
+        let mut partial = PartialNonogram::new(1, 1);
+        partial[(0, 0)] = PartCell::Space;
+
+        assert_eq!(layout, layout.apply_partial(&partial));
+    }
+
+    #[test]
+    fn layout_from_nonogram() {
+        let (layout, nonogram) = partial_layout();
+
+        assert_eq!(layout, Layout::from(&nonogram));
+    }
+
+    #[test]
+    fn layout_from_nonogram_colors() {
+        let a = Cell::Box { color: 'a' };
+        let b = Cell::Box { color: 'b' };
+        let nonogram = Nonogram::try_from(vec![vec![a, a, b, Cell::Space, b]]).unwrap();
+        let layout = Layout::from(&nonogram);
+
+        assert_eq!(
+            vec![Item::new('a', 2), Item::new('b', 1), Item::new('b', 1)],
+            layout.rows[0]
+        );
+        assert_eq!(vec![Item::new('b', 1)], layout.cols[2]);
+        assert!(layout.cols[3].is_empty());
+    }
+
+    #[test]
+    fn layout_from_nonogram_no_cols() {
+        let nonogram: Nonogram<char> = Nonogram::new(0, 3);
+        let layout = Layout::from(&nonogram);
+
+        assert!(layout.cols.is_empty());
+        assert_eq!(vec![Vec::<Item<char>>::new(); 3], layout.rows);
+    }
+
+    #[test]
+    fn layout_is_logic_solvable_invalid() {
+        let layout = Layout::new(vec![vec![Item::new('a', 2)]], vec![vec![Item::new('a', 1)]]);
+
+        assert!(!layout.is_logic_solvable());
+    }
+
+    #[test]
+    fn layout_diagnose() {
+        let cols = vec![vec![Item::new('a', 1)]; 2];
+        let rows = vec![vec![Item::new('a', 2), Item::new('a', 1)]];
+        let text = Layout::new(cols, rows).diagnose();
+
+        assert!(text
+            .contains("Row 1, items [2, 1] require at least 4 cells but the grid has 2 columns."));
+        assert_eq!(2, text.lines().count());
+    }
+
+    #[test]
+    fn layout_validate_min_len_detail() {
+        let cols = vec![vec![Item::new('a', 1)]; 8];
+        let rows = vec![vec![
+            Item::new('a', 3),
+            Item::new('b', 2),
+            Item::new('b', 0),
+            Item::new('b', 3),
+        ]];
+        let layout = Layout::new(cols, rows.clone());
+
+        let errors = layout.validate().unwrap_err();
+
+        assert!(matches!(
+            &errors[0],
+            LayoutError::RowTooLong {
+                index: 0,
+                min_len: 9,
+                items,
+                min_len_detail,
+                ..
+            } if *items == rows[0] && *min_len_detail == vec![3, 5, 5, 9]
+        ));
+    }
+
+    #[test]
+    fn layout_diagnose_valid() {
+        let layout = Layout::new(vec![vec![Item::new('a', 1)]], vec![vec![Item::new('a', 1)]]);
+
+        assert_eq!("", layout.diagnose());
+    }
+
+    #[test]
+    fn layout_remove_redundant_zeros() {
+        let (layout, _) = partial_layout();
+        let mut zeros = layout.clone();
+        zeros.cols[1].push(Item::new('a', 0));
+        zeros.cols[2].insert(1, Item::new('b', 0));
+        zeros.rows[0].insert(0, Item::new('a', 0));
+
+        zeros.remove_redundant_zeros();
+
+        assert_eq!(layout, zeros);
+    }
+
+    #[test]
+    fn layout_cleaned_solve() {
+        let (layout, nonogram) = partial_layout();
+        let mut zeros = layout.clone();
+        zeros.cols[2].insert(1, Item::new('a', 0));
+        zeros.rows[2].push(Item::new('a', 0));
+
+        let cleaned = zeros.clone().cleaned();
+
+        assert!(cleaned
+            .cols
+            .iter()
+            .chain(&cleaned.rows)
+            .flatten()
+            .all(|item| item.len > 0));
+        assert_eq!(
+            zeros.solve_unique(()).unwrap(),
+            cleaned.solve_unique(()).unwrap()
+        );
+        assert_eq!(nonogram, layout.solve_unique(()).unwrap());
+    }
+
+    #[test]
+    fn layout_transpose() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('b', 2)]];
+        let rows = vec![vec![Item::new('c', 3)]];
+        let layout = Layout::new(cols, rows).transpose();
+
+        assert_eq!(1, layout.cols.len());
+        assert_eq!(2, layout.rows.len());
+        assert_eq!('c', layout.cols[0][0].color);
+        assert_eq!('b', layout.rows[1][0].color);
+    }
+
+    #[test]
+    fn layout_sum_cols() {
+        let cols = vec![vec![Item::new('a', 2), Item::new('b', 1)], vec![]];
+        let layout = Layout::new(cols, Vec::new());
+
+        assert_eq!(3, layout.sum_cols());
+    }
+
+    #[test]
+    fn layout_sum_rows() {
+        let rows = vec![vec![Item::new('a', 2)], vec![Item::new('b', 4)]];
+        let layout = Layout::new(Vec::new(), rows);
+
+        assert_eq!(6, layout.sum_rows());
+    }
+
+    #[test]
+    fn layout_validate_sum_mismatch() {
+        let cols = vec![vec![Item::new('a', 2)]; 2];
+        let rows = vec![
+            vec![Item::new('a', 2)],
+            vec![Item::new('a', 2)],
+            vec![Item::new('a', 1)],
+        ];
+        let layout = Layout::new(cols, rows);
+
+        let errors = layout.validate().unwrap_err();
+
+        assert_eq!(
+            vec![LayoutError::SumMismatch {
+                row_sum: 5,
+                col_sum: 4
+            }],
+            errors
+        );
+    }
+
+    #[test]
+    fn layout_error_into_nonogram_error() {
+        let layout = Layout::new(vec![vec![Item::new(1, 2)]], vec![vec![Item::new(1, 2)]]);
+        let err: NonogramError = layout.validate().unwrap_err().into();
+
+        assert_eq!(NonogramError::InvalidLayout, err);
+    }
+
+    #[test]
+    fn layout_colors_used() {
+        let cols = vec![vec![Item::new('b', 1), Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)], vec![Item::new('c', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(vec!['b', 'a', 'c'], layout.colors_used());
+    }
+
+    #[test]
+    fn layout_colors_used_empty() {
+        let layout: Layout<char> = Layout::new(Vec::new(), Vec::new());
+
+        assert!(layout.colors_used().is_empty());
+    }
+
+    #[test]
+    fn layout_col_clues() {
+        let cols = vec![vec![Item::new('a', 1)], Vec::new(), vec![Item::new('b', 2)]];
+        let layout = Layout::new(cols, Vec::new());
+
+        assert_eq!(layout.cols.len(), layout.col_clues().len());
+        assert_eq!(layout.cols.len(), layout.col_clues().count());
+
+        for (clues, col) in layout.col_clues().zip(&layout.cols) {
+            assert!(std::ptr::eq(clues, col.as_slice()));
+        }
+    }
+
+    #[test]
+    fn layout_row_clues() {
+        let rows = vec![vec![Item::new('a', 1), Item::new('b', 1)], Vec::new()];
+        let layout = Layout::new(Vec::new(), rows);
+
+        assert_eq!(layout.rows.len(), layout.row_clues().len());
+        assert_eq!(layout.rows.len(), layout.row_clues().count());
+
+        for (clues, row) in layout.row_clues().zip(&layout.rows) {
+            assert!(std::ptr::eq(clues, row.as_slice()));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn layout_json_writer_reader() {
+        let cols = vec![vec![Item::new('a', 1), Item::new('b', 2)], Vec::new()];
+        let rows = vec![vec![Item::new('b', 3)]];
+        let layout = Layout::new(cols, rows);
+
+        let mut json = Vec::new();
+        layout.to_json_writer(&mut json).unwrap();
+        let target: Layout<char> = Layout::from_json_reader(json.as_slice()).unwrap();
+
+        assert_eq!(layout, target);
+    }
+
+    #[test]
+    fn layout_eq() {
+        let cols = vec![vec![Item::new('a', 1), Item::new('b', 2)]];
+        let rows = vec![vec![Item::new('a', 1)], vec![Item::new('b', 2)]];
+        let layout = Layout::new(cols.clone(), rows.clone());
+
+        assert_eq!(layout, Layout::new(cols.clone(), rows.clone()));
+        assert_ne!(layout, Layout::new(rows, cols));
+    }
+
+    #[test]
+    fn layout_ne_item() {
+        let layout = Layout::new(vec![vec![Item::new('a', 1)]], Vec::new());
+
+        assert_ne!(
+            layout,
+            Layout::new(vec![vec![Item::new('a', 2)]], Vec::new())
+        );
+        assert_ne!(
+            layout,
+            Layout::new(vec![vec![Item::new('b', 1)]], Vec::new())
+        );
+    }
+
+    #[test]
+    fn layout_solve() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(1, layout.solve(usize::MAX, ()).collection.len());
+    }
+
+    #[test]
+    fn layout_solve_sharded() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert_eq!(1, layout.solve_sharded(usize::MAX, ()).collection.len());
+    }
+
+    #[test]
+    fn layout_count_solutions_invalid() {
+        let cols = vec![vec![Item::new('a', 2)], vec![]];
+        let rows = vec![vec![Item::new('a', 2)], vec![]];
+
+        let count = Layout::new(cols, rows).count_solutions(usize::MAX, ());
+
+        assert_eq!(0, count.count);
+        assert!(matches!(count.status, Status::Complete));
+    }
+
+    #[test]
+    fn layout_solve_with_max_depth() {
+        let data = vec![vec![Item::new('a', 1)]; 3];
+        let layout = Layout::new(data.clone(), data);
+
+        let shallow = layout.clone().solve_with_max_depth(usize::MAX, 1, ());
+        let deep = layout.solve_with_max_depth(usize::MAX, usize::MAX, ());
+
+        assert!(matches!(shallow.status, Status::DepthExceeded));
+        assert!(shallow.len() < 6);
+        assert!(matches!(deep.status, Status::Complete));
+        assert_eq!(6, deep.len());
+    }
+
+    #[test]
+    fn layout_solve_with_max_depth_single_boxes() {
+        let data = vec![vec![Item::new('a', 1)]; 5];
+        let layout = Layout::new(data.clone(), data);
+
+        let solution = layout.solve_with_max_depth(usize::MAX, 2, ());
+
+        assert!(matches!(solution.status, Status::DepthExceeded));
+        assert!(solution.is_empty());
+    }
+
+    #[test]
+    fn layout_solve_with_hints() {
+        let cols = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)], vec![Item::new('a', 1)]];
+        let mut hints = PartialNonogram::new(2, 2);
+        hints[(0, 0)] = PartCell::Box { color: 'a' };
+
+        let solution = Layout::new(cols, rows).solve_with_hints(&hints, usize::MAX, ());
+
+        assert_eq!(1, solution.len());
+        assert_eq!(Cell::Box { color: 'a' }, solution.collection[0][(0, 0)]);
+        assert_eq!(Cell::Box { color: 'a' }, solution.collection[0][(1, 1)]);
+    }
+
+    #[test]
+    fn layout_solve_with_hints_conflict() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let mut hints = PartialNonogram::new(1, 1);
+        hints[(0, 0)] = PartCell::Space;
+
+        let solution = Layout::new(cols, rows).solve_with_hints(&hints, usize::MAX, ());
+
+        assert!(solution.is_empty());
+        assert!(matches!(solution.status, Status::Complete));
+    }
+
+    #[test]
+    #[should_panic]
+    fn layout_solve_with_hints_size_mismatch() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+
+        Layout::new(cols, rows).solve_with_hints(&PartialNonogram::new(2, 1), usize::MAX, ());
+    }
+
+    #[test]
+    fn layout_solve_unique_cancelled() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert!(matches!(
+            layout.solve_unique(Cancel),
+            Err(UniquenessError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn layout_solve_partial_invalid() {
+        let cols = vec![vec![Item::new('a', 2)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert!(matches!(
+            layout.solve_partial(()),
+            Err(PartialError::Invalid)
+        ));
+    }
+
+    #[test]
+    fn layout_solve_partial_cancelled() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let layout = Layout::new(cols, rows);
+
+        assert!(matches!(
+            layout.solve_partial(Cancel),
+            Err(PartialError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn validated_layout_new() {
+        let cols = vec![vec![Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1)]];
+        let layout = ValidatedLayout::new(Layout::new(cols, rows)).unwrap();
+
+        assert_eq!(1, layout.sum_cols());
+        assert_eq!(1, Layout::solve_validated(layout, usize::MAX, ()).len());
+    }
+
+    #[test]
+    fn validated_layout_new_invalid() {
+        let cols = vec![vec![Item::new('a', 1), Item::new('a', 1)]];
+        let rows = vec![vec![Item::new('a', 1), Item::new('a', 1)]];
+        let result = ValidatedLayout::new(Layout::new(cols, rows));
+
+        let expected = vec![
+            LayoutError::ColTooLong {
+                index: 0,
+                min_len: 3,
+                line_len: 1,
+                items: vec![Item::new('a', 1), Item::new('a', 1)],
+                min_len_detail: vec![1, 3],
+            },
+            LayoutError::RowTooLong {
+                index: 0,
+                min_len: 3,
+                line_len: 1,
+                items: vec![Item::new('a', 1), Item::new('a', 1)],
+                min_len_detail: vec![1, 3],
+            },
+        ];
+        assert_eq!(Some(expected), result.err());
     }
 }
@@ -1,31 +1,317 @@
-mod algo;
+/// The solver internals, exposed for inspection and analysis.
+///
+/// See `ALGORITHM.md` for an explanation of the algorithm.
+pub mod algo;
 mod cancel;
+mod format;
+#[cfg(feature = "gen")]
+pub mod gen;
+mod hint;
 mod layout;
 mod nonogram;
+mod puzzle;
+pub mod render;
 
 pub use cancel::{Cancelled, Token};
-pub use layout::{Item, Layout};
-pub use nonogram::{Cell, Nonogram};
+#[cfg(feature = "compact")]
+pub use format::compact::DecodeError;
+#[cfg(feature = "format_griddlers")]
+pub use format::griddlers::{
+    from_griddlers_json, from_griddlers_json_with, ColorMapper, GriddlersError,
+};
+pub use format::text::LayoutParseError;
+pub use hint::{hint, Hint, HintReason};
+pub use layout::{
+    min_gap, min_length, solve_parallel_count, Item, Layout, LayoutError, MonochromeError,
+    MultiLayoutError, UniquenessError, ValidatedLayout,
+};
+#[cfg(feature = "serde")]
+pub use nonogram::serde_compact;
+pub use nonogram::{Cell, CellError, Nonogram, PartialNonogram, Rows, RowsMut};
+pub use puzzle::{Puzzle, PuzzleMetadata};
 
+use algo::PartCell;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::ops::Index;
+
+/// An error raised when constructing or accessing a [Nonogram].
+#[derive(Clone, PartialEq, Debug)]
+pub enum NonogramError {
+    /// The layout doesn't result in a valid nonogram.
+    InvalidLayout,
+    /// The rows have different lengths.
+    DimensionMismatch,
+    /// The position is outside of the nonogram.
+    OutOfBounds { col: usize, row: usize },
+    /// The byte doesn't represent a cell.
+    InvalidCell(u8),
+    /// The value of a cell is not known yet.
+    Unsolved,
+}
+
+/// How hard a puzzle is to solve by hand.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Difficulty {
+    /// Every line is fully known from its own clues.
+    Trivial,
+    /// Line propagation solves every cell.
+    Easy,
+    /// Line propagation stalls after knowing at least half of the cells.
+    Medium,
+    /// Line propagation stalls earlier, but some line overlaps with itself.
+    Hard,
+    /// Line propagation stalls early without any overlap, or finds a contradiction.
+    Expert,
+}
 
 /// The status when a [Solution] was created.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
 pub enum Status {
     /// The operation was completed.
     Complete,
     /// The collection was full.
+    ///
+    /// More solutions exist than the limit allows, the solutions beyond the limit have been dropped.
     Full,
     /// The operation has been cancelled.
     Cancelled,
+    /// Some branches have been skipped as they require more nested guesses than allowed.
+    ///
+    /// See [Layout::solve_with_max_depth].
+    DepthExceeded,
 }
 
 /// A collection of all solutions to a [Layout].
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
 pub struct Solution<T: Copy> {
     /// All found solutions to the [Layout].
     pub collection: Vec<Nonogram<T>>,
     /// The status when creating this [Solution].
     pub status: Status,
 }
+
+impl<T: Copy> Solution<T> {
+    /// Returns the first solution, if any.
+    pub fn first(&self) -> Option<&Nonogram<T>> {
+        self.collection.first()
+    }
+
+    /// Keeps only the first `n` solutions.
+    pub fn take(mut self, n: usize) -> Self {
+        self.collection.truncate(n);
+        self
+    }
+
+    /// Returns the amount of solutions.
+    pub fn len(&self) -> usize {
+        self.collection.len()
+    }
+
+    /// Checks if no solution has been found.
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+    }
+}
+
+/// The reason a [PartialSolution] could not be created or converted.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PartialError {
+    /// The layout contradicts itself.
+    Invalid,
+    /// The operation has been cancelled.
+    Cancelled,
+    /// Some cells could not be deduced without guessing.
+    Incomplete,
+}
+
+/// The amount of solutions to a [Layout].
+///
+/// Returned by [Layout::count_solutions].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug)]
+pub struct SolutionCount {
+    /// The amount of solutions found.
+    pub count: usize,
+    /// The status when counting stopped.
+    pub status: Status,
+}
+
+/// The cells of a [Layout] which can be deduced without guessing.
+///
+/// Returned by [Layout::solve_partial].
+pub struct PartialSolution<T> {
+    /// The cells, row by row.
+    pub cells: Vec<Vec<PartCell<T>>>,
+    /// Whether every cell is known.
+    pub is_complete: bool,
+    /// The amount of known cells.
+    pub forced_count: usize,
+}
+
+impl<T> PartialSolution<T> {
+    /// Creates a partial solution from the cells, row by row.
+    pub fn new(cells: Vec<Vec<PartCell<T>>>) -> Self {
+        let forced_count = cells.iter().flatten().filter(|c| c.is_known()).count();
+        let is_complete = cells.iter().flatten().all(PartCell::is_known);
+
+        Self {
+            cells,
+            is_complete,
+            forced_count,
+        }
+    }
+
+    /// Returns the column count.
+    pub fn cols(&self) -> usize {
+        self.cells.first().map(Vec::len).unwrap_or(0)
+    }
+
+    /// Returns the row count.
+    pub fn rows(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+impl<T: Copy> PartialSolution<T> {
+    /// Converts the partial solution into a nonogram.
+    ///
+    /// Fails if the partial solution is not complete.
+    pub fn to_nonogram(self) -> Result<Nonogram<T>, PartialError> {
+        if !self.is_complete {
+            return Err(PartialError::Incomplete);
+        }
+        let mut nonogram = Nonogram::new(self.cols(), self.rows());
+
+        for (row, cells) in self.cells.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                nonogram[(col, row)] = cell.try_into().unwrap();
+            }
+        }
+        Ok(nonogram)
+    }
+}
+
+impl<T> Index<(usize, usize)> for PartialSolution<T> {
+    type Output = PartCell<T>;
+
+    /// Returns the cell at `(col, row)`.
+    fn index(&self, (col, row): (usize, usize)) -> &Self::Output {
+        &self.cells[row][col]
+    }
+}
+
+impl<T: Copy + Ord> Solution<T> {
+    /// Sorts the solutions, see [Nonogram] for the order.
+    ///
+    /// Solutions are found in parallel, so their order varies between runs.
+    /// After sorting, the order is deterministic if all solutions have been found.
+    pub fn sort(&mut self) {
+        self.collection.sort();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn solution(len: usize, status: Status) -> Solution<()> {
+        Solution {
+            collection: vec![Nonogram::new(2, 2); len],
+            status,
+        }
+    }
+
+    #[test]
+    fn solution_sort() {
+        let mut a = Nonogram::new(1, 2);
+        a[(0, 0)] = Cell::Box { color: 2 };
+        let mut b = Nonogram::new(1, 2);
+        b[(0, 1)] = Cell::Box { color: 1 };
+
+        let mut solution = Solution {
+            collection: vec![a.clone(), b.clone()],
+            status: Status::Complete,
+        };
+        solution.sort();
+
+        assert_eq!(vec![b, a], solution.collection);
+    }
+
+    #[test]
+    fn partial_solution_new() {
+        let cells = vec![
+            vec![PartCell::Box { color: 'a' }, PartCell::Empty],
+            vec![PartCell::Space, PartCell::Empty],
+            vec![PartCell::Space, PartCell::Box { color: 'b' }],
+        ];
+        let partial = PartialSolution::new(cells);
+
+        assert_eq!(2, partial.cols());
+        assert_eq!(3, partial.rows());
+        assert_eq!(4, partial.forced_count);
+        assert!(!partial.is_complete);
+        assert!(matches!(partial[(0, 1)], PartCell::Space));
+        assert!(matches!(partial[(1, 2)], PartCell::Box { color: 'b' }));
+    }
+
+    #[test]
+    fn partial_solution_to_nonogram() {
+        let cells = vec![
+            vec![PartCell::Box { color: 'a' }, PartCell::Space],
+            vec![PartCell::Space, PartCell::Box { color: 'b' }],
+        ];
+        let nonogram = PartialSolution::new(cells).to_nonogram().unwrap();
+
+        assert_eq!(Cell::Box { color: 'a' }, nonogram[(0, 0)]);
+        assert_eq!(Cell::Space, nonogram[(1, 0)]);
+        assert_eq!(Cell::Box { color: 'b' }, nonogram[(1, 1)]);
+    }
+
+    #[test]
+    fn partial_solution_to_nonogram_incomplete() {
+        let cells = vec![vec![PartCell::Box { color: 'a' }, PartCell::Empty]];
+        let result = PartialSolution::new(cells).to_nonogram();
+
+        assert!(matches!(result, Err(PartialError::Incomplete)));
+    }
+
+    #[test]
+    fn solution_first() {
+        let solution = solution(2, Status::Complete);
+
+        assert!(solution.first().is_some());
+    }
+
+    #[test]
+    fn solution_first_empty() {
+        let solution = solution(0, Status::Complete);
+
+        assert!(solution.first().is_none());
+    }
+
+    #[test]
+    fn solution_take_zero() {
+        let solution = solution(3, Status::Full).take(0);
+
+        assert!(solution.is_empty());
+        assert!(matches!(solution.status, Status::Full));
+    }
+
+    #[test]
+    fn solution_take_more() {
+        let solution = solution(3, Status::Complete).take(5);
+
+        assert_eq!(3, solution.len());
+    }
+
+    #[test]
+    fn solution_take_less() {
+        let solution = solution(3, Status::Complete).take(2);
+
+        assert_eq!(2, solution.len());
+    }
+}
@@ -4,10 +4,16 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(feature = "serde")]
 use serde::de::Error;
 
-use std::ops::{Index, IndexMut};
+use crate::algo::PartCell;
+use crate::NonogramError;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::{Add, Index, IndexMut, Range};
 
 /// A cell of a [Nonogram].
-#[derive(Copy, Clone, PartialEq, Debug)]
+///
+/// Cells are ordered with [Cell::Space] before any [Cell::Box].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Cell<T> {
     /// A box with some color of type `T`.
@@ -16,6 +22,69 @@ pub enum Cell<T> {
     Space,
 }
 
+impl<T: Ord> Ord for Cell<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Cell::Space, Cell::Space) => Ordering::Equal,
+            (Cell::Space, Cell::Box { .. }) => Ordering::Less,
+            (Cell::Box { .. }, Cell::Space) => Ordering::Greater,
+            (Cell::Box { color: a }, Cell::Box { color: b }) => a.cmp(b),
+        }
+    }
+}
+
+impl<T: Ord> PartialOrd for Cell<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Cell<T> {
+    /// Checks if the cell is a box.
+    pub fn is_box(&self) -> bool {
+        matches!(self, Cell::Box { .. })
+    }
+
+    /// Checks if the cell is a space.
+    pub fn is_space(&self) -> bool {
+        matches!(self, Cell::Space)
+    }
+
+    /// Returns the color of a box or [None] for a space.
+    pub fn color(self) -> Option<T> {
+        match self {
+            Cell::Box { color } => Some(color),
+            Cell::Space => None,
+        }
+    }
+
+    /// Returns the color of a box.
+    ///
+    /// # Panics
+    /// If the cell is a space.
+    pub fn unwrap_color(self) -> T {
+        match self {
+            Cell::Box { color } => color,
+            Cell::Space => panic!("called unwrap_color on Space"),
+        }
+    }
+}
+
+impl TryFrom<u8> for Cell<bool> {
+    type Error = NonogramError;
+
+    /// Converts `0` to a space and `1` to a box.
+    ///
+    /// Fails with [NonogramError::InvalidCell] for any other byte.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Cell::Space),
+            1 => Ok(Cell::Box { color: true }),
+            n => Err(NonogramError::InvalidCell(n)),
+        }
+    }
+}
+
 /// A nonogram with a fix size containing some [Cell]s.
 /// `T` is the type used to represent colors.
 /// ```rust
@@ -29,21 +98,95 @@ pub enum Cell<T> {
 ///
 /// let value = n[(0, 3)];
 /// ```
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Nonogram<T> {
     cols: usize,
     rows: usize,
     data: Vec<Cell<T>>,
 }
 
-impl<T: Clone> Nonogram<T> {
+/// An error raised by [Nonogram::set_checked].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CellError {
+    /// The position is outside of the nonogram.
+    OutOfBounds { col: usize, row: usize },
+    /// The cell already contains a different box.
+    AlreadySet,
+}
+
+impl Display for CellError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CellError::OutOfBounds { col, row } => {
+                write!(f, "cell ({col}, {row}) is out of bounds")
+            }
+            CellError::AlreadySet => write!(f, "cell is already set"),
+        }
+    }
+}
+
+impl std::error::Error for CellError {}
+
+impl<T> Nonogram<T> {
     /// Constructs a new nonogram.
+    ///
+    /// # Panics
+    /// If `cols * rows` overflows `usize`.
     pub fn new(cols: usize, rows: usize) -> Self {
+        let len = cols
+            .checked_mul(rows)
+            .unwrap_or_else(|| panic!("nonogram of {cols}x{rows} cells overflows usize"));
+
         Self {
             cols,
             rows,
-            data: vec![Cell::Space; cols * rows],
+            data: (0..len).map(|_| Cell::Space).collect(),
+        }
+    }
+
+    /// Constructs a nonogram from its cells in row-major order.
+    ///
+    /// Fails with [NonogramError::DimensionMismatch] if there aren't exactly `cols * rows` cells.
+    pub fn from_flat_vec(
+        cols: usize,
+        rows: usize,
+        data: Vec<Cell<T>>,
+    ) -> Result<Self, NonogramError> {
+        if cols.checked_mul(rows) != Some(data.len()) {
+            return Err(NonogramError::DimensionMismatch);
+        }
+        Ok(Self { cols, rows, data })
+    }
+
+    /// Constructs a nonogram from its rows.
+    ///
+    /// Fails with [NonogramError::DimensionMismatch] if the rows have different lengths.
+    /// No rows result in an empty nonogram.
+    pub fn from_rows(rows: Vec<Vec<Cell<T>>>) -> Result<Self, NonogramError> {
+        let row_len = rows.len();
+        let col_len = rows.first().map(Vec::len).unwrap_or(0);
+
+        if rows.iter().any(|row| row.len() != col_len) {
+            return Err(NonogramError::DimensionMismatch);
         }
+        Nonogram::from_flat_vec(col_len, row_len, rows.into_iter().flatten().collect())
+    }
+
+    /// Returns the largest side length of a square nonogram
+    /// whose cells can be allocated on the current target.
+    ///
+    /// The cells are the only data stored per cell, so they make up the whole footprint.
+    pub fn max_dimension() -> usize {
+        let cell_size = std::mem::size_of::<Cell<T>>().max(1);
+
+        (isize::MAX as usize / cell_size).isqrt()
+    }
+}
+
+impl<T: Clone> Nonogram<T> {
+    /// Returns a copy of the cells in row-major order.
+    pub fn to_flat_vec(&self) -> Vec<Cell<T>> {
+        self.data.clone()
     }
 }
 
@@ -58,6 +201,92 @@ impl<T> Nonogram<T> {
         self.rows
     }
 
+    /// Returns the cells of a row.
+    ///
+    /// # Panics
+    /// If the row is out of bounds.
+    pub fn row(&self, row: usize) -> &[Cell<T>] {
+        assert!(row < self.rows);
+
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Returns the cells of a row mutably.
+    ///
+    /// # Panics
+    /// If the row is out of bounds.
+    pub fn row_mut(&mut self, row: usize) -> &mut [Cell<T>] {
+        assert!(row < self.rows);
+
+        &mut self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Returns the amount of boxes.
+    pub fn box_count(&self) -> usize {
+        self.data.iter().filter(|cell| cell.is_box()).count()
+    }
+
+    /// Returns the amount of spaces.
+    pub fn space_count(&self) -> usize {
+        self.data.iter().filter(|cell| cell.is_space()).count()
+    }
+
+    /// Returns the share of cells which are boxes.
+    ///
+    /// A nonogram without any cells has a density of zero.
+    pub fn density(&self) -> f64 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        self.box_count() as f64 / self.data.len() as f64
+    }
+
+    /// Checks if the value of every cell is known, which is always the case.
+    ///
+    /// Use a [PartialNonogram] and [PartialNonogram::is_fully_solved] to detect forgotten cells,
+    /// then convert it with [TryFrom].
+    pub fn is_fully_solved(&self) -> bool {
+        // A nonogram has no unknown state: Nonogram::new fills it with spaces.
+        // Tracking which cells have been written would cost a byte per cell in every solution
+        // and bookkeeping in every mutable accessor, while PartialNonogram already has PartCell::Empty.
+        true
+    }
+
+    /// Returns the value of a cell without panicking.
+    ///
+    /// Fails with [NonogramError::OutOfBounds], where indexing would panic.
+    pub fn get_checked(&self, col: usize, row: usize) -> Result<&Cell<T>, NonogramError> {
+        if col >= self.cols || row >= self.rows {
+            return Err(NonogramError::OutOfBounds { col, row });
+        }
+        Ok(&self[(col, row)])
+    }
+
+    /// Sets the value of a cell without panicking.
+    ///
+    /// Like [crate::algo::PartCell::Empty] for lines, [Cell::Space] is the
+    /// unset value of [Nonogram::new]. A cell is set once it holds a box
+    /// and may then only be written with the same value again.
+    /// Prefer this over [IndexMut], which panics on invalid positions
+    /// and overrides any value.
+    pub fn set_checked(&mut self, col: usize, row: usize, value: Cell<T>) -> Result<(), CellError>
+    where
+        T: PartialEq,
+    {
+        if col >= self.cols || row >= self.rows {
+            return Err(CellError::OutOfBounds { col, row });
+        }
+        let index = self.index_of((col, row));
+        let cell = &mut self.data[index];
+
+        if cell.is_box() && *cell != value {
+            return Err(CellError::AlreadySet);
+        }
+        *cell = value;
+
+        Ok(())
+    }
+
     /// Returns the index of a cell by column and row.
     ///
     /// # Panics
@@ -66,28 +295,213 @@ impl<T> Nonogram<T> {
         assert!(pos.0 < self.cols);
         assert!(pos.1 < self.rows);
 
-        pos.1 * self.cols + pos.0
+        let index = pos
+            .1
+            .checked_mul(self.cols)
+            .and_then(|offset| offset.checked_add(pos.0))
+            .unwrap_or_else(|| panic!("index of {pos:?} overflows usize"));
+
+        assert!(index < self.data.len());
+        index
     }
 }
 
-impl<T: Copy> TryFrom<Vec<Vec<Cell<T>>>> for Nonogram<T> {
-    type Error = ();
+impl<T: Copy> Nonogram<T> {
+    /// Copies the cells into a row.
+    ///
+    /// # Panics
+    /// If the row is out of bounds or the length of `cells` doesn't match the column count.
+    pub fn set_row(&mut self, row: usize, cells: &[Cell<T>]) {
+        assert!(row < self.rows);
+        assert_eq!(self.cols, cells.len());
 
-    fn try_from(value: Vec<Vec<Cell<T>>>) -> Result<Self, Self::Error> {
-        let row_len = value.len();
-        let col_len = value.iter().map(Vec::len).next().unwrap_or(0);
+        self.row_mut(row).copy_from_slice(cells);
+    }
+
+    /// Copies the cells into a column.
+    ///
+    /// # Panics
+    /// If the column is out of bounds or the length of `cells` doesn't match the row count.
+    pub fn set_col(&mut self, col: usize, cells: &[Cell<T>]) {
+        assert!(col < self.cols);
+        assert_eq!(self.rows, cells.len());
+
+        for (row, cell) in cells.iter().enumerate() {
+            self[(col, row)] = *cell;
+        }
+    }
+
+    /// Copies a rectangular region into a new nonogram.
+    ///
+    /// # Panics
+    /// If the region is out of bounds.
+    pub fn clone_region(&self, cols: Range<usize>, rows: Range<usize>) -> Nonogram<T> {
+        assert!(cols.start <= cols.end && cols.end <= self.cols);
+        assert!(rows.start <= rows.end && rows.end <= self.rows);
+
+        let mut region = Nonogram::new(cols.len(), rows.len());
+
+        for (target, row) in rows.enumerate() {
+            let source = row * self.cols + cols.start..row * self.cols + cols.end;
+            let offset = target * region.cols..(target + 1) * region.cols;
+
+            region.data[offset].copy_from_slice(&self.data[source]);
+        }
+        region
+    }
+
+    /// Copies another nonogram into this one with its top left corner at the given offset.
+    ///
+    /// # Panics
+    /// If `src` doesn't fit into this nonogram at the given offset.
+    pub fn paste(&mut self, col_offset: usize, row_offset: usize, src: &Nonogram<T>) {
+        assert!(col_offset.saturating_add(src.cols) <= self.cols);
+        assert!(row_offset.saturating_add(src.rows) <= self.rows);
+
+        for row in 0..src.rows {
+            let start = (row_offset + row) * self.cols + col_offset;
+            self.data[start..start + src.cols].copy_from_slice(src.row(row));
+        }
+    }
+
+    /// Returns the corner cells.
+    ///
+    /// Order: `[top_left, top_right, bottom_left, bottom_right]`
+    ///
+    /// # Panics
+    /// If the nonogram has no cells.
+    pub fn corners(&self) -> [Cell<T>; 4] {
+        let (right, bottom) = (self.cols - 1, self.rows - 1);
+
+        [
+            self[(0, 0)],
+            self[(right, 0)],
+            self[(0, bottom)],
+            self[(right, bottom)],
+        ]
+    }
+
+    /// Stacks another nonogram below this one.
+    ///
+    /// The narrower nonogram is padded with [Cell::Space] on the right.
+    pub fn vconcat(&self, other: &Nonogram<T>) -> Nonogram<T> {
+        let mut result = Nonogram::new(self.cols.max(other.cols), self.rows + other.rows);
+
+        result.paste(0, 0, self);
+        result.paste(0, self.rows, other);
+        result
+    }
+
+    /// Returns an adapter which displays the nonogram with the given palette.
+    ///
+    /// Each cell is two characters wide.
+    /// Boxes use the character returned by `palette` and spaces are blank.
+    /// Rows are separated by newlines, without a trailing newline.
+    pub fn display_with_palette<'a, F: Fn(T) -> char + 'a>(
+        &'a self,
+        palette: F,
+    ) -> impl Display + 'a {
+        PaletteDisplay {
+            nonogram: self,
+            palette,
+        }
+    }
+}
+
+/// Places two nonograms side by side.
+///
+/// The shorter nonogram is padded with [Cell::Space] on the bottom.
+impl<T: Copy> Add for Nonogram<T> {
+    type Output = Nonogram<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut result = Nonogram::new(self.cols + rhs.cols, self.rows.max(rhs.rows));
+
+        result.paste(0, 0, &self);
+        result.paste(self.cols, 0, &rhs);
+        result
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl<T: PartialEq> Nonogram<T> {
+    /// Asserts that two nonograms are equal, like [assert_eq].
+    ///
+    /// On failure, both nonograms are drawn side by side with `■` for boxes,
+    /// followed by a grid marking the differing cells with `X`.
+    ///
+    /// # Panics
+    /// If the nonograms differ.
+    pub fn assert_eq(expected: &Nonogram<T>, actual: &Nonogram<T>) {
+        if (expected.cols, expected.rows) != (actual.cols, actual.rows) {
+            panic!(
+                "nonograms differ in size: expected {}x{}, actual {}x{}",
+                expected.cols, expected.rows, actual.cols, actual.rows
+            );
+        }
+        if expected.data == actual.data {
+            return;
+        }
+        let draw = |cell: &Cell<T>| if cell.is_box() { '■' } else { ' ' };
+        let mut message = String::from("nonograms differ:\nexpected | actual | diff\n");
+
+        for row in 0..expected.rows {
+            let (expected, actual) = (expected.row(row), actual.row(row));
+            let diff = expected
+                .iter()
+                .zip(actual)
+                .map(|(a, b)| if a == b { '.' } else { 'X' });
 
-        let mut nonogram = Nonogram::new(col_len, row_len);
+            message.extend(expected.iter().map(draw));
+            message.push_str(" | ");
+            message.extend(actual.iter().map(draw));
+            message.push_str(" | ");
+            message.extend(diff);
+            message.push('\n');
+        }
+        panic!("{}", message);
+    }
+}
+
+/// Displays a nonogram, see [Nonogram::display_with_palette].
+struct PaletteDisplay<'a, T, F> {
+    nonogram: &'a Nonogram<T>,
+    palette: F,
+}
 
-        for row in 0..row_len {
-            if value[row].len() != col_len {
-                return Err(());
+impl<T: Copy, F: Fn(T) -> char> Display for PaletteDisplay<'_, T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in 0..self.nonogram.rows {
+            if row > 0 {
+                writeln!(f)?;
             }
-            for col in 0..col_len {
-                nonogram[(col, row)] = value[row][col];
+            for cell in self.nonogram.row(row) {
+                match cell {
+                    Cell::Box { color } => {
+                        let c = (self.palette)(*color);
+
+                        write!(f, "{c}{c}")?;
+                    }
+                    Cell::Space => write!(f, "  ")?,
+                }
             }
         }
-        Ok(nonogram)
+        Ok(())
+    }
+}
+
+impl<T> Default for Nonogram<T> {
+    /// Constructs a nonogram without any cells.
+    fn default() -> Self {
+        Nonogram::new(0, 0)
+    }
+}
+
+impl<T> TryFrom<Vec<Vec<Cell<T>>>> for Nonogram<T> {
+    type Error = NonogramError;
+
+    fn try_from(value: Vec<Vec<Cell<T>>>) -> Result<Self, Self::Error> {
+        Nonogram::from_rows(value)
     }
 }
 
@@ -107,6 +521,21 @@ impl<T: Copy> From<Nonogram<T>> for Vec<Vec<Cell<T>>> {
     }
 }
 
+impl<T: Eq> Eq for Nonogram<T> {}
+
+/// Nonograms are ordered by their dimensions and then by their cells, row by row.
+impl<T: Ord> Ord for Nonogram<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.cols, self.rows, &self.data).cmp(&(other.cols, other.rows, &other.data))
+    }
+}
+
+impl<T: Ord> PartialOrd for Nonogram<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<T> Index<(usize, usize)> for Nonogram<T> {
     type Output = Cell<T>;
 
@@ -117,6 +546,10 @@ impl<T> Index<(usize, usize)> for Nonogram<T> {
     }
 }
 
+/// Overrides a cell unconditionally.
+///
+/// # Panics
+/// If the position is out of bounds; use [Nonogram::set_checked] instead.
 impl<T> IndexMut<(usize, usize)> for Nonogram<T> {
     fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
         let index = self.index_of(pos);
@@ -125,87 +558,597 @@ impl<T> IndexMut<(usize, usize)> for Nonogram<T> {
     }
 }
 
-#[cfg(feature = "serde")]
-impl<T: Copy + Serialize> Serialize for Nonogram<T> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let data: Vec<Vec<Cell<T>>> = self.clone().into();
+impl<T> Extend<((usize, usize), Cell<T>)> for Nonogram<T> {
+    /// Sets every `(col, row)` to its cell.
+    ///
+    /// # Panics
+    /// If a position is out of bounds.
+    fn extend<I: IntoIterator<Item = ((usize, usize), Cell<T>)>>(&mut self, iter: I) {
+        for (pos, cell) in iter {
+            self[pos] = cell;
+        }
+    }
+}
 
-        data.serialize(serializer)
+impl<'a, T> IntoIterator for &'a Nonogram<T> {
+    type Item = &'a [Cell<T>];
+    type IntoIter = Rows<'a, T>;
+
+    /// Iterates over the rows.
+    ///
+    /// A nonogram without columns yields an empty slice per row.
+    fn into_iter(self) -> Self::IntoIter {
+        Rows {
+            data: &self.data,
+            cols: self.cols,
+            rows: self.rows,
+        }
     }
 }
 
-#[cfg(feature = "serde")]
-impl<'a, T: Copy + Deserialize<'a>> Deserialize<'a> for Nonogram<T> {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'a>,
-    {
-        let data: Vec<Vec<Cell<T>>> = Vec::deserialize(deserializer)?;
+impl<'a, T> IntoIterator for &'a mut Nonogram<T> {
+    type Item = &'a mut [Cell<T>];
+    type IntoIter = RowsMut<'a, T>;
 
-        data.try_into()
-            .map_err(|_| Error::custom("Failed to construct a nonogram."))
+    /// Iterates mutably over the rows.
+    ///
+    /// A nonogram without columns yields an empty slice per row.
+    fn into_iter(self) -> Self::IntoIter {
+        RowsMut {
+            data: &mut self.data,
+            cols: self.cols,
+            rows: self.rows,
+        }
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// An iterator over the rows of a [Nonogram].
+#[derive(Debug)]
+pub struct Rows<'a, T> {
+    data: &'a [Cell<T>],
+    cols: usize,
+    rows: usize,
+}
 
-    #[test]
-    fn nonogram_cols() {
-        let n: Nonogram<()> = Nonogram::new(3, 7);
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = &'a [Cell<T>];
 
-        assert_eq!(3, n.cols());
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rows == 0 {
+            return None;
+        }
+        let (row, data) = self.data.split_at(self.cols);
+        self.data = data;
+        self.rows -= 1;
+
+        Some(row)
     }
 
-    #[test]
-    fn nonogram_rows() {
-        let n: Nonogram<()> = Nonogram::new(5, 2);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.rows, Some(self.rows))
+    }
+}
 
-        assert_eq!(2, n.rows());
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.rows == 0 {
+            return None;
+        }
+        let (data, row) = self.data.split_at(self.data.len() - self.cols);
+        self.data = data;
+        self.rows -= 1;
+
+        Some(row)
     }
+}
 
-    #[test]
-    fn nonogram_index_mut() {
-        let mut n = Nonogram::new(5, 2);
+impl<T> ExactSizeIterator for Rows<'_, T> {}
 
-        n[(3, 1)] = Cell::Box { color: 5 };
+/// An iterator over the mutable rows of a [Nonogram].
+#[derive(Debug)]
+pub struct RowsMut<'a, T> {
+    data: &'a mut [Cell<T>],
+    cols: usize,
+    rows: usize,
+}
 
-        assert!(matches!(n[(3, 1)], Cell::Box { color: 5 }));
-    }
+impl<'a, T> Iterator for RowsMut<'a, T> {
+    type Item = &'a mut [Cell<T>];
 
-    #[test]
-    #[should_panic]
-    fn nonogram_index_mut_col_oob() {
-        let n: Nonogram<()> = Nonogram::new(4, 8);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rows == 0 {
+            return None;
+        }
+        let (row, data) = std::mem::take(&mut self.data).split_at_mut(self.cols);
+        self.data = data;
+        self.rows -= 1;
 
-        n[(4, 0)];
+        Some(row)
     }
 
-    #[test]
-    #[should_panic]
-    fn nonogram_index_mut_row_oob() {
-        let n: Nonogram<()> = Nonogram::new(9, 5);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.rows, Some(self.rows))
+    }
+}
+
+impl<T> DoubleEndedIterator for RowsMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.rows == 0 {
+            return None;
+        }
+        let data = std::mem::take(&mut self.data);
+        let (data, row) = data.split_at_mut(data.len() - self.cols);
+        self.data = data;
+        self.rows -= 1;
 
-        n[(0, 5)];
+        Some(row)
     }
+}
 
-    #[test]
-    fn vec_from_nonogram() {
-        let mut nonogram = Nonogram::new(2, 3);
-        nonogram[(0, 0)] = Cell::Space;
-        nonogram[(0, 1)] = Cell::Box { color: 3 };
-        nonogram[(0, 2)] = Cell::Space;
-        nonogram[(1, 0)] = Cell::Box { color: 4 };
-        nonogram[(1, 1)] = Cell::Box { color: 4 };
-        nonogram[(1, 2)] = Cell::Space;
+impl<T> ExactSizeIterator for RowsMut<'_, T> {}
 
-        let vec: Vec<Vec<Cell<i32>>> = nonogram.into();
+/// A nonogram where some cells might not be known yet.
+///
+/// Used to pass cells placed by a player to the solver.
+///
+/// ```rust
+/// use nonogram_rs::algo::PartCell;
+/// use nonogram_rs::PartialNonogram;
+///
+/// let mut hints = PartialNonogram::new(5, 5);
+/// hints[(2, 3)] = PartCell::Box { color: 'a' };
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct PartialNonogram<T> {
+    cols: usize,
+    rows: usize,
+    data: Vec<PartCell<T>>,
+}
 
-        assert_eq!(3, vec.len());
+impl<T> PartialNonogram<T> {
+    /// Constructs a new partial nonogram where all cells are unknown.
+    ///
+    /// # Panics
+    /// If `cols * rows` overflows `usize`.
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let len = cols
+            .checked_mul(rows)
+            .unwrap_or_else(|| panic!("nonogram of {cols}x{rows} cells overflows usize"));
+
+        Self {
+            cols,
+            rows,
+            data: (0..len).map(|_| PartCell::Empty).collect(),
+        }
+    }
+}
+
+impl<T> PartialNonogram<T> {
+    /// Returns the column count.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the row count.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the amount of known cells.
+    pub fn known_count(&self) -> usize {
+        self.data.iter().filter(|cell| cell.is_known()).count()
+    }
+
+    /// Checks if the value of every cell is known,
+    /// e.g. to detect a forgotten cell before converting it into a [Nonogram].
+    ///
+    /// This is the check [Nonogram::is_fully_solved] can't do,
+    /// as a nonogram can't tell a forgotten cell from a space.
+    pub fn is_fully_solved(&self) -> bool {
+        self.data.iter().all(PartCell::is_known)
+    }
+}
+
+impl<T> TryFrom<PartialNonogram<T>> for Nonogram<T> {
+    type Error = NonogramError;
+
+    /// Fails with [NonogramError::Unsolved] if a cell is unknown.
+    fn try_from(partial: PartialNonogram<T>) -> Result<Self, Self::Error> {
+        let data = partial
+            .data
+            .into_iter()
+            .map(Cell::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            cols: partial.cols,
+            rows: partial.rows,
+            data,
+        })
+    }
+}
+
+impl<T: Copy> From<&Nonogram<T>> for PartialNonogram<T> {
+    fn from(nonogram: &Nonogram<T>) -> Self {
+        Self {
+            cols: nonogram.cols,
+            rows: nonogram.rows,
+            data: nonogram.data.iter().map(|cell| (*cell).into()).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for PartialNonogram<T> {
+    type Output = PartCell<T>;
+
+    fn index(&self, (col, row): (usize, usize)) -> &Self::Output {
+        assert!(col < self.cols);
+        assert!(row < self.rows);
+
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for PartialNonogram<T> {
+    fn index_mut(&mut self, (col, row): (usize, usize)) -> &mut Self::Output {
+        assert!(col < self.cols);
+        assert!(row < self.rows);
+
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Copy + Serialize> Serialize for Nonogram<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let data: Vec<Vec<Cell<T>>> = self.clone().into();
+
+        data.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T: Copy + Deserialize<'a>> Deserialize<'a> for Nonogram<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        let data: Vec<Vec<Cell<T>>> = Vec::deserialize(deserializer)?;
+
+        data.try_into()
+            .map_err(|_| Error::custom("Failed to construct a nonogram."))
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: Copy + Serialize> Nonogram<T> {
+    /// Writes the nonogram as MessagePack, using the same schema as JSON.
+    ///
+    /// # Panics
+    /// If the colors can't be serialized.
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(self).expect("failed to serialize the nonogram")
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: Copy + serde::de::DeserializeOwned> Nonogram<T> {
+    /// Reads a nonogram from MessagePack, see [Nonogram::to_msgpack].
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(bytes)
+    }
+}
+
+/// A compact representation of a [Nonogram] for `#[serde(with = "nonogram_rs::serde_compact")]`.
+///
+/// The nonogram is stored as `{"cols": N, "rows": M, "data": [...]}`
+/// with the cells in row-major order.
+/// [Cell::Space] is stored as `0` and [Cell::Box] as its color plus one,
+/// so the color has to convert to and from `u32`, e.g. `u8` or `char`.
+#[cfg(feature = "serde")]
+pub mod serde_compact {
+    use super::{Cell, Nonogram};
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Compact {
+        cols: usize,
+        rows: usize,
+        data: Vec<u32>,
+    }
+
+    /// Serializes a nonogram in the compact representation.
+    ///
+    /// Fails if a color converts to `u32::MAX`.
+    pub fn serialize<T, S>(nonogram: &Nonogram<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<u32>,
+        S: Serializer,
+    {
+        let data = nonogram
+            .data
+            .iter()
+            .map(|cell| match *cell {
+                Cell::Box { color } => color
+                    .into()
+                    .checked_add(1)
+                    .ok_or_else(|| S::Error::custom("The color is out of range.")),
+                Cell::Space => Ok(0),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Compact {
+            cols: nonogram.cols,
+            rows: nonogram.rows,
+            data,
+        }
+        .serialize(serializer)
+    }
+
+    /// Deserializes a nonogram from the compact representation.
+    pub fn deserialize<'a, T, D>(deserializer: D) -> Result<Nonogram<T>, D::Error>
+    where
+        T: TryFrom<u32>,
+        D: Deserializer<'a>,
+    {
+        let compact = Compact::deserialize(deserializer)?;
+        let data = compact
+            .data
+            .into_iter()
+            .map(|value| match value {
+                0 => Ok(Cell::Space),
+                value => T::try_from(value - 1)
+                    .map(|color| Cell::Box { color })
+                    .map_err(|_| D::Error::custom("The color is out of range.")),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Nonogram::from_flat_vec(compact.cols, compact.rows, data)
+            .map_err(|_| D::Error::custom("Failed to construct a nonogram."))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Item, Layout};
+
+    #[test]
+    fn cell_is_box() {
+        let cell = Cell::Box { color: 2 };
+
+        assert!(cell.is_box());
+        assert!(!cell.is_space());
+    }
+
+    #[test]
+    fn cell_is_space() {
+        let cell: Cell<()> = Cell::Space;
+
+        assert!(!cell.is_box());
+        assert!(cell.is_space());
+    }
+
+    #[test]
+    fn cell_color() {
+        assert_eq!(Some(5_u8), Cell::Box { color: 5 }.color());
+        assert_eq!(None, Cell::<u8>::Space.color());
+    }
+
+    #[test]
+    fn cell_try_from_u8() {
+        assert_eq!(Ok(Cell::Space), Cell::try_from(0));
+        assert_eq!(Ok(Cell::Box { color: true }), Cell::try_from(1));
+        assert_eq!(Err(NonogramError::InvalidCell(2)), Cell::try_from(2));
+    }
+
+    #[test]
+    fn cell_unwrap_color() {
+        assert_eq!('a', Cell::Box { color: 'a' }.unwrap_color());
+    }
+
+    #[test]
+    #[should_panic(expected = "called unwrap_color on Space")]
+    fn cell_unwrap_color_space() {
+        Cell::<char>::Space.unwrap_color();
+    }
+
+    #[test]
+    fn nonogram_cols() {
+        let n: Nonogram<()> = Nonogram::new(3, 7);
+
+        assert_eq!(3, n.cols());
+    }
+
+    #[test]
+    fn nonogram_rows() {
+        let n: Nonogram<()> = Nonogram::new(5, 2);
+
+        assert_eq!(2, n.rows());
+    }
+
+    #[test]
+    fn nonogram_box_count() {
+        let mut n = Nonogram::new(3, 3);
+
+        for col in 0..3 {
+            for row in 0..3 {
+                n[(col, row)] = Cell::Box { color: 1 };
+            }
+        }
+
+        assert_eq!(9, n.box_count());
+        assert_eq!(0, n.space_count());
+        assert_eq!(1.0, n.density());
+    }
+
+    #[test]
+    fn nonogram_box_count_mixed() {
+        let mut n = Nonogram::new(4, 2);
+        n[(0, 0)] = Cell::Box { color: 1 };
+        n[(3, 1)] = Cell::Box { color: 2 };
+
+        assert_eq!(2, n.box_count());
+        assert_eq!(6, n.space_count());
+        assert_eq!(0.25, n.density());
+    }
+
+    #[test]
+    fn nonogram_is_fully_solved() {
+        let n: Nonogram<()> = Nonogram::new(3, 2);
+
+        assert!(n.is_fully_solved());
+    }
+
+    #[test]
+    fn nonogram_density_empty() {
+        let n: Nonogram<()> = Nonogram::new(0, 0);
+
+        assert_eq!(0.0, n.density());
+    }
+
+    #[test]
+    fn nonogram_set_checked() {
+        let mut nonogram: Nonogram<()> = Nonogram::new(2, 3);
+
+        assert_eq!(Ok(()), nonogram.set_checked(1, 2, Cell::Box { color: () }));
+        assert_eq!(Cell::Box { color: () }, nonogram[(1, 2)]);
+    }
+
+    #[test]
+    fn nonogram_get_checked() {
+        let mut nonogram: Nonogram<()> = Nonogram::new(2, 3);
+        nonogram[(1, 2)] = Cell::Box { color: () };
+
+        assert_eq!(Ok(&Cell::Box { color: () }), nonogram.get_checked(1, 2));
+        assert_eq!(
+            Err(NonogramError::OutOfBounds { col: 2, row: 0 }),
+            nonogram.get_checked(2, 0)
+        );
+        assert_eq!(
+            Err(NonogramError::OutOfBounds { col: 0, row: 3 }),
+            nonogram.get_checked(0, 3)
+        );
+    }
+
+    #[test]
+    fn nonogram_set_checked_out_of_bounds() {
+        let mut nonogram: Nonogram<()> = Nonogram::new(2, 3);
+
+        let err = nonogram.set_checked(2, 7, Cell::Space).unwrap_err();
+
+        assert_eq!(CellError::OutOfBounds { col: 2, row: 7 }, err);
+        assert_eq!("cell (2, 7) is out of bounds", err.to_string());
+    }
+
+    #[test]
+    fn nonogram_set_checked_already_set() {
+        let mut nonogram: Nonogram<()> = Nonogram::new(2, 3);
+        nonogram[(0, 1)] = Cell::Box { color: () };
+
+        assert_eq!(
+            Err(CellError::AlreadySet),
+            nonogram.set_checked(0, 1, Cell::Space)
+        );
+        assert_eq!(Cell::Box { color: () }, nonogram[(0, 1)]);
+        assert_eq!("cell is already set", CellError::AlreadySet.to_string());
+    }
+
+    #[test]
+    fn nonogram_set_checked_same_value() {
+        let mut nonogram = Nonogram::new(2, 3);
+        nonogram[(0, 1)] = Cell::Box { color: 4 };
+
+        assert_eq!(Ok(()), nonogram.set_checked(0, 1, Cell::Box { color: 4 }));
+        assert_eq!(
+            Err(CellError::AlreadySet),
+            nonogram.set_checked(0, 1, Cell::Box { color: 5 })
+        );
+        assert_eq!(Cell::Box { color: 4 }, nonogram[(0, 1)]);
+    }
+
+    #[test]
+    fn nonogram_set_checked_solved() {
+        let cols = vec![vec![Item::new((), 1)], Vec::new()];
+        let rows = vec![vec![Item::new((), 1)], Vec::new()];
+        let mut solution = Layout::new(cols, rows).solve(1, ()).collection;
+        let nonogram = &mut solution[0];
+
+        assert_eq!(
+            Err(CellError::AlreadySet),
+            nonogram.set_checked(0, 0, Cell::Space)
+        );
+        assert_eq!(Ok(()), nonogram.set_checked(1, 1, Cell::Box { color: () }));
+        assert_eq!(Cell::Box { color: () }, nonogram[(1, 1)]);
+    }
+
+    #[test]
+    fn nonogram_index_mut() {
+        let mut n = Nonogram::new(5, 2);
+
+        n[(3, 1)] = Cell::Box { color: 5 };
+
+        assert!(matches!(n[(3, 1)], Cell::Box { color: 5 }));
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_index_mut_col_oob() {
+        let n: Nonogram<()> = Nonogram::new(4, 8);
+
+        let _ = n[(4, 0)];
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_index_mut_row_oob() {
+        let n: Nonogram<()> = Nonogram::new(9, 5);
+
+        let _ = n[(0, 5)];
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_new_too_large() {
+        let _: Nonogram<()> = Nonogram::new(usize::MAX / 2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn nonogram_new_overflow() {
+        let _: Nonogram<()> = Nonogram::new(usize::MAX / 2, 3);
+    }
+
+    #[test]
+    fn nonogram_max_dimension() {
+        let max = Nonogram::<u32>::max_dimension();
+        let cells = max * max;
+
+        let cell_size = std::mem::size_of::<Cell<u32>>();
+
+        assert!(cells * cell_size <= isize::MAX as usize);
+        assert!((max + 1) * (max + 1) * cell_size > isize::MAX as usize);
+        assert!(Nonogram::<()>::max_dimension() >= max);
+    }
+
+    #[test]
+    fn vec_from_nonogram() {
+        let mut nonogram = Nonogram::new(2, 3);
+        nonogram[(0, 0)] = Cell::Space;
+        nonogram[(0, 1)] = Cell::Box { color: 3 };
+        nonogram[(0, 2)] = Cell::Space;
+        nonogram[(1, 0)] = Cell::Box { color: 4 };
+        nonogram[(1, 1)] = Cell::Box { color: 4 };
+        nonogram[(1, 2)] = Cell::Space;
+
+        let vec: Vec<Vec<Cell<i32>>> = nonogram.into();
+
+        assert_eq!(3, vec.len());
         assert_eq!(2, vec[0].len());
         assert_eq!(2, vec[0].len());
         assert_eq!(2, vec[0].len());
@@ -236,6 +1179,579 @@ mod test {
         assert!(matches!(nonogram[(2, 1)], Cell::Space));
     }
 
+    #[test]
+    fn nonogram_row() {
+        let mut n = Nonogram::new(3, 2);
+        n[(1, 1)] = Cell::Box { color: 2 };
+
+        assert_eq!(n.data.as_ptr(), n.row(0).as_ptr());
+        assert_eq!(
+            &[Cell::Space, Cell::Box { color: 2 }, Cell::Space],
+            n.row(1)
+        );
+    }
+
+    #[test]
+    fn nonogram_row_mut() {
+        let mut n = Nonogram::new(3, 2);
+        n.row_mut(1)[2] = Cell::Box { color: 5 };
+
+        assert_eq!(Cell::Box { color: 5 }, n[(2, 1)]);
+        assert_eq!(Cell::Space, n[(2, 0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_row_oob() {
+        let n: Nonogram<i32> = Nonogram::new(3, 2);
+
+        let _ = n.row(n.rows());
+    }
+
+    #[test]
+    fn cell_ord() {
+        assert!(Cell::Space < Cell::Box { color: 0 });
+        assert!(Cell::Box { color: 1 } < Cell::Box { color: 2 });
+        assert_eq!(Ordering::Equal, Cell::<i32>::Space.cmp(&Cell::Space));
+    }
+
+    #[test]
+    fn nonogram_ord() {
+        let mut a = Nonogram::new(2, 2);
+        let mut b = Nonogram::new(2, 2);
+        a[(1, 0)] = Cell::Box { color: 1 };
+        b[(0, 1)] = Cell::Box { color: 1 };
+
+        assert!(b < a);
+        assert_eq!(Ordering::Equal, a.cmp(&a.clone()));
+    }
+
+    #[test]
+    fn nonogram_set_row() {
+        let mut n = Nonogram::new(3, 2);
+        n.set_row(1, &[Cell::Box { color: 1 }; 3]);
+        n.set_row(1, &[Cell::Space; 3]);
+
+        for col in 0..3 {
+            assert_eq!(Cell::Space, n[(col, 1)]);
+        }
+        assert_eq!(0, n.box_count());
+    }
+
+    #[test]
+    fn nonogram_set_col() {
+        let mut n = Nonogram::new(3, 2);
+        n.set_col(2, &[Cell::Box { color: 4 }; 2]);
+
+        assert_eq!(Cell::Box { color: 4 }, n[(2, 0)]);
+        assert_eq!(Cell::Box { color: 4 }, n[(2, 1)]);
+        assert_eq!(Cell::Space, n[(1, 1)]);
+        assert_eq!(2, n.box_count());
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_set_row_len_mismatch() {
+        let mut n: Nonogram<i32> = Nonogram::new(3, 2);
+
+        n.set_row(0, &[Cell::Space; 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_set_col_len_mismatch() {
+        let mut n: Nonogram<i32> = Nonogram::new(3, 2);
+
+        n.set_col(0, &[Cell::Space; 3]);
+    }
+
+    #[test]
+    fn nonogram_clone_region() {
+        let mut n = Nonogram::new(4, 3);
+        n[(1, 1)] = Cell::Box { color: 1 };
+        n[(2, 2)] = Cell::Box { color: 2 };
+
+        let region = n.clone_region(1..3, 1..3);
+
+        assert_eq!(2, region.cols());
+        assert_eq!(2, region.rows());
+        assert_eq!(Cell::Box { color: 1 }, region[(0, 0)]);
+        assert_eq!(Cell::Box { color: 2 }, region[(1, 1)]);
+        assert_eq!(2, region.box_count());
+    }
+
+    #[test]
+    fn nonogram_clone_region_empty() {
+        let n: Nonogram<i32> = Nonogram::new(4, 3);
+        let region = n.clone_region(2..2, 0..3);
+
+        assert_eq!(0, region.cols());
+        assert_eq!(3, region.rows());
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_clone_region_oob() {
+        let n: Nonogram<i32> = Nonogram::new(4, 3);
+
+        n.clone_region(2..5, 0..3);
+    }
+
+    #[test]
+    fn nonogram_paste() {
+        let mut n = Nonogram::new(5, 5);
+        let src = Nonogram::try_from(vec![vec![Cell::Box { color: 1 }; 2]; 2]).unwrap();
+
+        n.paste(1, 1, &src);
+
+        for col in 0..5 {
+            for row in 0..5 {
+                let inside = (1..3).contains(&col) && (1..3).contains(&row);
+                let expected = if inside {
+                    Cell::Box { color: 1 }
+                } else {
+                    Cell::Space
+                };
+                assert_eq!(expected, n[(col, row)]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_paste_oob() {
+        let mut n: Nonogram<i32> = Nonogram::new(5, 5);
+
+        n.paste(4, 0, &Nonogram::new(2, 2));
+    }
+
+    #[test]
+    fn nonogram_display_with_palette() {
+        let mut n: Nonogram<u8> = Nonogram::new(3, 2);
+        n[(0, 0)] = Cell::Box { color: 0 };
+        n[(2, 1)] = Cell::Box { color: 1 };
+
+        let text = n
+            .display_with_palette(|color| if color == 0 { 'R' } else { 'B' })
+            .to_string();
+
+        assert_eq!("RR    \n    BB", text);
+    }
+
+    #[test]
+    fn nonogram_assert_eq() {
+        let mut n = Nonogram::new(2, 2);
+        n[(1, 0)] = Cell::Box { color: 1 };
+
+        Nonogram::assert_eq(&n, &n.clone());
+    }
+
+    #[test]
+    fn nonogram_assert_eq_diff() {
+        let mut expected = Nonogram::new(3, 2);
+        expected[(0, 0)] = Cell::Box { color: 1 };
+        expected[(1, 1)] = Cell::Box { color: 1 };
+        let mut actual = expected.clone();
+        actual[(1, 1)] = Cell::Space;
+        actual[(2, 1)] = Cell::Box { color: 2 };
+
+        let panic = std::panic::catch_unwind(|| Nonogram::assert_eq(&expected, &actual));
+        let message = *panic.unwrap_err().downcast::<String>().unwrap();
+
+        assert!(message.contains("■   | ■   | ...\n"));
+        assert!(message.contains(" ■  |   ■ | .XX\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "nonograms differ in size")]
+    fn nonogram_assert_eq_size() {
+        Nonogram::<i32>::assert_eq(&Nonogram::new(2, 1), &Nonogram::new(1, 2));
+    }
+
+    #[test]
+    fn nonogram_default() {
+        let n: Nonogram<i32> = Nonogram::default();
+
+        assert_eq!(0, n.cols());
+        assert_eq!(0, n.rows());
+    }
+
+    #[test]
+    fn nonogram_corners() {
+        let mut n = Nonogram::new(3, 2);
+        n[(0, 0)] = Cell::Box { color: 1 };
+        n[(2, 0)] = Cell::Box { color: 2 };
+        n[(0, 1)] = Cell::Box { color: 3 };
+        n[(1, 1)] = Cell::Box { color: 5 };
+        n[(2, 1)] = Cell::Box { color: 4 };
+
+        let mut corners = n.corners();
+
+        assert_eq!([1, 2, 3, 4].map(|color| Cell::Box { color }), corners);
+
+        corners[0] = Cell::Space;
+
+        assert_eq!(Cell::Space, corners[0]);
+        assert_eq!(Cell::Box { color: 1 }, n[(0, 0)]);
+    }
+
+    #[test]
+    fn nonogram_corners_single() {
+        let mut n = Nonogram::new(1, 1);
+        n[(0, 0)] = Cell::Box { color: 1 };
+
+        assert_eq!([Cell::Box { color: 1 }; 4], n.corners());
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_corners_empty() {
+        Nonogram::<i32>::new(0, 0).corners();
+    }
+
+    #[test]
+    fn nonogram_add() {
+        let a = Nonogram::try_from(vec![vec![Cell::Box { color: 1 }; 2]; 2]).unwrap();
+        let b = Nonogram::try_from(vec![vec![Cell::Box { color: 2 }; 3]; 2]).unwrap();
+
+        let n = a + b;
+
+        assert_eq!(5, n.cols());
+        assert_eq!(2, n.rows());
+
+        for row in 0..2 {
+            let expected = [1, 1, 2, 2, 2].map(|color| Cell::Box { color });
+
+            assert_eq!(expected, n.row(row));
+        }
+    }
+
+    #[test]
+    fn nonogram_add_pads_bottom() {
+        let a = Nonogram::try_from(vec![vec![Cell::Box { color: 1 }; 2]; 3]).unwrap();
+        let b = Nonogram::try_from(vec![vec![Cell::Box { color: 2 }]]).unwrap();
+
+        let n = a + b;
+
+        assert_eq!(3, n.cols());
+        assert_eq!(3, n.rows());
+        assert_eq!(Cell::Box { color: 2 }, n[(2, 0)]);
+        assert_eq!(Cell::Space, n[(2, 1)]);
+        assert_eq!(Cell::Space, n[(2, 2)]);
+        assert_eq!(7, n.box_count());
+    }
+
+    #[test]
+    fn nonogram_add_empty() {
+        let a = Nonogram::try_from(vec![vec![Cell::Box { color: 1 }; 2]; 2]).unwrap();
+
+        let n = a.clone() + Nonogram::new(0, 0);
+
+        assert_eq!(a, n);
+    }
+
+    #[test]
+    fn nonogram_vconcat() {
+        let a = Nonogram::try_from(vec![vec![Cell::Box { color: 1 }; 2]; 2]).unwrap();
+        let b = Nonogram::try_from(vec![vec![Cell::Box { color: 2 }; 2]; 1]).unwrap();
+
+        let n = a.vconcat(&b);
+
+        assert_eq!(2, n.cols());
+        assert_eq!(3, n.rows());
+        assert_eq!([Cell::Box { color: 1 }; 2], n.row(1));
+        assert_eq!([Cell::Box { color: 2 }; 2], n.row(2));
+    }
+
+    #[test]
+    fn nonogram_vconcat_pads_right() {
+        let a = Nonogram::try_from(vec![vec![Cell::Box { color: 1 }]]).unwrap();
+        let b = Nonogram::try_from(vec![vec![Cell::Box { color: 2 }; 3]]).unwrap();
+
+        let n = a.vconcat(&b);
+
+        assert_eq!(3, n.cols());
+        assert_eq!(2, n.rows());
+        assert_eq!([Cell::Box { color: 1 }, Cell::Space, Cell::Space], n.row(0));
+        assert_eq!([Cell::Box { color: 2 }; 3], n.row(1));
+    }
+
+    #[test]
+    fn nonogram_flat_vec_round_trip() {
+        let mut n = Nonogram::new(3, 2);
+        n[(0, 1)] = Cell::Box { color: 'a' };
+        n[(2, 0)] = Cell::Box { color: 'b' };
+
+        let flat = n.to_flat_vec();
+
+        assert_eq!(Cell::Box { color: 'b' }, flat[2]);
+        assert_eq!(Cell::Box { color: 'a' }, flat[3]);
+        assert_eq!(
+            n,
+            Nonogram::from_flat_vec(n.cols(), n.rows(), flat).unwrap()
+        );
+    }
+
+    #[test]
+    fn nonogram_from_flat_vec_mismatch() {
+        let data = vec![Cell::<()>::Space; 5];
+
+        assert_eq!(
+            Some(NonogramError::DimensionMismatch),
+            Nonogram::from_flat_vec(3, 2, data).err()
+        );
+    }
+
+    #[test]
+    fn nonogram_new_without_clone() {
+        struct NoClone;
+
+        let n: Nonogram<NoClone> = Nonogram::new(3, 3);
+
+        assert_eq!(9, n.space_count());
+    }
+
+    #[test]
+    fn nonogram_new_rc() {
+        let mut n: Nonogram<std::rc::Rc<str>> = Nonogram::new(3, 3);
+        n[(1, 2)] = Cell::Box {
+            color: "red".into(),
+        };
+
+        assert_eq!(1, n.box_count());
+    }
+
+    #[test]
+    fn partial_nonogram_new_without_clone() {
+        struct NoClone;
+
+        let n: PartialNonogram<NoClone> = PartialNonogram::new(2, 2);
+
+        assert_eq!(0, n.known_count());
+    }
+
+    #[test]
+    fn partial_nonogram_new() {
+        let n: PartialNonogram<i32> = PartialNonogram::new(3, 2);
+
+        assert_eq!(3, n.cols());
+        assert_eq!(2, n.rows());
+        assert_eq!(0, n.known_count());
+        assert!(matches!(n[(2, 1)], PartCell::Empty));
+    }
+
+    #[test]
+    fn partial_nonogram_index_mut() {
+        let mut n = PartialNonogram::new(3, 2);
+        n[(2, 1)] = PartCell::Box { color: 4 };
+        n[(0, 1)] = PartCell::Space;
+
+        assert_eq!(2, n.known_count());
+        assert!(matches!(n[(2, 1)], PartCell::Box { color: 4 }));
+        assert!(matches!(n[(0, 1)], PartCell::Space));
+        assert!(matches!(n[(1, 1)], PartCell::Empty));
+    }
+
+    #[test]
+    #[should_panic]
+    fn partial_nonogram_index_oob() {
+        let n: PartialNonogram<i32> = PartialNonogram::new(3, 2);
+
+        let _ = n[(3, 0)];
+    }
+
+    #[test]
+    fn partial_nonogram_is_fully_solved() {
+        let mut n = PartialNonogram::new(2, 1);
+        n[(1, 0)] = PartCell::Box { color: 1 };
+
+        assert!(!n.is_fully_solved());
+
+        n[(0, 0)] = PartCell::Space;
+
+        assert!(n.is_fully_solved());
+    }
+
+    #[test]
+    fn partial_nonogram_into_nonogram() {
+        let mut n = PartialNonogram::new(2, 1);
+        n[(0, 0)] = PartCell::Space;
+        n[(1, 0)] = PartCell::Box { color: 1 };
+
+        let nonogram = Nonogram::try_from(n).unwrap();
+
+        assert_eq!([Cell::Space, Cell::Box { color: 1 }], nonogram.row(0));
+    }
+
+    #[test]
+    fn partial_nonogram_into_nonogram_forgotten_cell() {
+        let mut n = PartialNonogram::new(2, 1);
+        n[(1, 0)] = PartCell::Box { color: 1 };
+
+        assert_eq!(Err(NonogramError::Unsolved), Nonogram::try_from(n));
+    }
+
+    #[test]
+    fn partial_nonogram_from_nonogram() {
+        let mut nonogram = Nonogram::new(2, 2);
+        nonogram[(1, 0)] = Cell::Box { color: 1 };
+
+        let n = PartialNonogram::from(&nonogram);
+
+        assert_eq!(4, n.known_count());
+        assert!(matches!(n[(1, 0)], PartCell::Box { color: 1 }));
+        assert!(matches!(n[(0, 1)], PartCell::Space));
+    }
+
+    #[test]
+    fn nonogram_into_iter() {
+        let mut nonogram = Nonogram::new(2, 3);
+        nonogram[(1, 0)] = Cell::Box { color: 1 };
+        nonogram[(0, 2)] = Cell::Box { color: 2 };
+
+        let rows: Vec<&[Cell<i32>]> = (&nonogram).into_iter().collect();
+
+        assert_eq!(3, rows.len());
+        assert_eq!(&[Cell::Space, Cell::Box { color: 1 }], rows[0]);
+        assert_eq!(&[Cell::Space, Cell::Space], rows[1]);
+        assert_eq!(&[Cell::Box { color: 2 }, Cell::Space], rows[2]);
+    }
+
+    #[test]
+    fn nonogram_into_iter_empty() {
+        let mut nonogram: Nonogram<i32> = Nonogram::new(0, 3);
+
+        assert_eq!(3, (&nonogram).into_iter().len());
+        assert!((&nonogram).into_iter().all(|row| row.is_empty()));
+        assert_eq!(3, (&mut nonogram).into_iter().count());
+    }
+
+    #[test]
+    fn nonogram_into_iter_rev() {
+        let mut nonogram = Nonogram::new(2, 3);
+        nonogram[(1, 2)] = Cell::Box { color: 7 };
+
+        let rows: Vec<_> = (&nonogram).into_iter().rev().collect();
+        assert_eq!(nonogram.row(2), rows[0]);
+        assert_eq!(nonogram.row(0), rows[2]);
+
+        (&mut nonogram).into_iter().next_back().unwrap()[0] = Cell::Box { color: 5 };
+        assert_eq!(Cell::Box { color: 5 }, nonogram[(0, 2)]);
+    }
+
+    #[test]
+    fn nonogram_into_iter_mut() {
+        let mut nonogram = Nonogram::new(3, 2);
+
+        for (index, row) in (&mut nonogram).into_iter().enumerate() {
+            assert_eq!(3, row.len());
+            row[index] = Cell::Box { color: 7 };
+        }
+
+        assert_eq!(Cell::Box { color: 7 }, nonogram[(0, 0)]);
+        assert_eq!(Cell::Box { color: 7 }, nonogram[(1, 1)]);
+        assert_eq!(2, nonogram.box_count());
+    }
+
+    #[test]
+    fn nonogram_from_vec_dimension_mismatch() {
+        let vec = vec![
+            vec![Cell::Box { color: 3 }, Cell::Space, Cell::Space],
+            vec![Cell::Box { color: 2 }, Cell::Box { color: 5 }],
+        ];
+
+        let nonogram: Result<Nonogram<i32>, _> = vec.try_into();
+
+        assert_eq!(Some(NonogramError::DimensionMismatch), nonogram.err());
+    }
+
+    #[test]
+    fn nonogram_extend() {
+        let mut n = Nonogram::new(5, 5);
+        n[(2, 3)] = Cell::Box { color: 'a' };
+
+        n.extend((0..5).flat_map(|col| (0..5).map(move |row| ((col, row), Cell::Space))));
+
+        assert_eq!(25, n.space_count());
+    }
+
+    #[test]
+    #[should_panic]
+    fn nonogram_extend_out_of_bounds() {
+        let mut n: Nonogram<char> = Nonogram::new(2, 2);
+
+        n.extend([((2, 0), Cell::Space)]);
+    }
+
+    #[test]
+    fn nonogram_from_rows() {
+        let rows = vec![
+            vec![Cell::Box { color: 3 }, Cell::Space],
+            vec![Cell::Space, Cell::Box { color: 5 }],
+            vec![Cell::Space, Cell::Space],
+        ];
+        let nonogram = Nonogram::from_rows(rows).unwrap();
+
+        assert_eq!(2, nonogram.cols());
+        assert_eq!(3, nonogram.rows());
+        assert_eq!(Cell::Box { color: 5 }, nonogram[(1, 1)]);
+    }
+
+    #[test]
+    fn nonogram_from_rows_jagged() {
+        let rows = vec![
+            vec![Cell::Space; 2],
+            vec![Cell::Space; 3],
+            vec![Cell::Space; 2],
+        ];
+
+        assert_eq!(
+            Some(NonogramError::DimensionMismatch),
+            Nonogram::<()>::from_rows(rows).err()
+        );
+    }
+
+    #[test]
+    fn nonogram_from_rows_empty() {
+        let nonogram = Nonogram::<()>::from_rows(Vec::new()).unwrap();
+
+        assert_eq!(0, nonogram.cols());
+        assert_eq!(0, nonogram.rows());
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct CompactNonogram(#[serde(with = "crate::serde_compact")] Nonogram<u8>);
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_compact_round_trip() {
+        let mut src = Nonogram::new(4, 3);
+        src[(0, 0)] = Cell::Box { color: 0 };
+        src[(3, 1)] = Cell::Box { color: 7 };
+        src[(2, 2)] = Cell::Box { color: 255 };
+
+        let json = serde_json::to_string(&CompactNonogram(src.clone())).unwrap();
+        let target: CompactNonogram = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            r#"{"cols":4,"rows":3,"data":[1,0,0,0,0,0,0,8,0,0,256,0]}"#,
+            json
+        );
+        assert_eq!(src, target.0);
+        assert!(json.len() < serde_json::to_string(&src).unwrap().len());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_compact_invalid() {
+        let mismatch = r#"{"cols":2,"rows":2,"data":[0,0,0]}"#;
+        let color = r#"{"cols":1,"rows":1,"data":[257]}"#;
+
+        assert!(serde_json::from_str::<CompactNonogram>(mismatch).is_err());
+        assert!(serde_json::from_str::<CompactNonogram>(color).is_err());
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn serialize_deserialize() {
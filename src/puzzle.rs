@@ -0,0 +1,104 @@
+use crate::{Difficulty, Layout, Nonogram, Token, UniquenessError};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// Descriptive information about a [Puzzle].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct PuzzleMetadata {
+    pub title: String,
+    pub author: String,
+    pub difficulty: Option<Difficulty>,
+    pub created: Option<SystemTime>,
+}
+
+/// A layout together with its metadata and, once known, its solution.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Puzzle<T: Copy> {
+    pub layout: Layout<T>,
+    pub metadata: PuzzleMetadata,
+    pub solution: Option<Nonogram<T>>,
+}
+
+impl<T: Copy> Puzzle<T> {
+    /// Creates a puzzle without a solution.
+    pub fn new(layout: Layout<T>, metadata: PuzzleMetadata) -> Self {
+        Self {
+            layout,
+            metadata,
+            solution: None,
+        }
+    }
+}
+
+impl<T: Copy + PartialEq + Send + Sync> Puzzle<T> {
+    /// Checks if a solution is attached and it is the unique solution of the layout.
+    pub fn verify(&self) -> bool {
+        match &self.solution {
+            Some(solution) => self.layout.clone().solve_unique(()).as_ref() == Ok(solution),
+            None => false,
+        }
+    }
+
+    /// Solves the layout and attaches the solution.
+    ///
+    /// The previous solution is kept if the layout doesn't have exactly one solution.
+    pub fn solve_and_attach(&mut self, token: impl Token) -> Result<(), UniquenessError> {
+        self.solution = Some(self.layout.clone().solve_unique(token)?);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Cell, Item};
+
+    fn puzzle() -> Puzzle<char> {
+        let cols = vec![vec![Item::new('a', 1)], Vec::new()];
+        let rows = vec![vec![Item::new('a', 1)]];
+
+        Puzzle::new(Layout::new(cols, rows), PuzzleMetadata::default())
+    }
+
+    #[test]
+    fn puzzle_verify_without_solution() {
+        assert!(!puzzle().verify());
+    }
+
+    #[test]
+    fn puzzle_solve_and_attach() {
+        let mut puzzle = puzzle();
+
+        puzzle.solve_and_attach(()).unwrap();
+
+        assert!(puzzle.verify());
+    }
+
+    #[test]
+    fn puzzle_verify_wrong_solution() {
+        let mut puzzle = puzzle();
+        let mut solution = Nonogram::new(2, 1);
+        solution[(1, 0)] = Cell::Box { color: 'a' };
+        puzzle.solution = Some(solution);
+
+        assert!(!puzzle.verify());
+    }
+
+    #[test]
+    fn puzzle_solve_and_attach_ambiguous() {
+        let cols = vec![vec![Item::new('a', 1)]; 2];
+        let rows = vec![vec![Item::new('a', 1)]; 2];
+        let mut puzzle = Puzzle::new(Layout::new(cols, rows), PuzzleMetadata::default());
+
+        assert_eq!(
+            Err(UniquenessError::MultipleSolutions(2)),
+            puzzle.solve_and_attach(())
+        );
+        assert_eq!(None, puzzle.solution);
+    }
+}
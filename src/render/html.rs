@@ -0,0 +1,91 @@
+//! Renders nonograms as HTML tables.
+
+use crate::{Cell, Nonogram};
+
+/// Renders a nonogram as an HTML table with one square `<td>` per cell.
+///
+/// # Parameters
+/// * `nonogram`: The nonogram to render.
+/// * `cell_px`: The width and height of a cell in pixels.
+/// * `palette`: Maps the color of a box to a CSS color.
+pub fn to_html_table<'a, T: Copy>(
+    nonogram: &Nonogram<T>,
+    cell_px: u32,
+    palette: &dyn Fn(T) -> &'a str,
+) -> String {
+    let mut html = String::from("<table style=\"border-collapse: collapse\"><tbody>");
+
+    for row in 0..nonogram.rows() {
+        html.push_str("<tr>");
+
+        for col in 0..nonogram.cols() {
+            let background = match nonogram[(col, row)] {
+                Cell::Box { color } => format!("background-color: {}", escape(palette(color))),
+                Cell::Space => "background: white".to_string(),
+            };
+            html.push_str(&format!(
+                "<td style=\"width: {cell_px}px; height: {cell_px}px; padding: 0; {background}\"></td>"
+            ));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</tbody></table>");
+    html
+}
+
+/// Escapes a string for use inside a quoted attribute.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    fn palette(color: char) -> &'static str {
+        match color {
+            'r' => "red",
+            _ => "black",
+        }
+    }
+
+    #[test]
+    fn to_html_table_cells() {
+        let mut nonogram = Nonogram::new(3, 2);
+        nonogram[(0, 0)] = Cell::Box { color: 'r' };
+        nonogram[(2, 1)] = Cell::Box { color: 'b' };
+
+        let html = to_html_table(&nonogram, 12, &palette);
+        let document = Html::parse_fragment(&html);
+        let td = Selector::parse("table > tbody > tr > td").unwrap();
+        let styles: Vec<_> = document
+            .select(&td)
+            .map(|cell| cell.value().attr("style").unwrap().to_string())
+            .collect();
+
+        assert!(document.errors.is_empty());
+        assert_eq!(6, styles.len());
+        assert!(styles[0].contains("background-color: red"));
+        assert!(styles[1].contains("background: white"));
+        assert!(styles[5].contains("background-color: black"));
+        assert!(styles.iter().all(|style| style.contains("width: 12px")));
+    }
+
+    #[test]
+    fn to_html_table_escapes_palette() {
+        let nonogram = Nonogram::try_from(vec![vec![Cell::Box { color: () }]]).unwrap();
+        let html = to_html_table(&nonogram, 1, &|_| "\"><script>");
+
+        assert!(!html.contains("<script>"));
+        assert_eq!(
+            1,
+            Html::parse_fragment(&html)
+                .select(&Selector::parse("td").unwrap())
+                .count()
+        );
+    }
+}
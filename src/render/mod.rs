@@ -0,0 +1,5 @@
+//! Renderers turning nonograms into other formats.
+
+#[cfg(feature = "html")]
+pub mod html;
+pub mod ppm;
@@ -0,0 +1,71 @@
+//! Renders nonograms as binary PPM (portable pixmap) images.
+
+use crate::{Cell, Nonogram};
+
+/// Renders a nonogram as a binary PPM image with a square block of pixels per cell.
+///
+/// # Parameters
+/// * `nonogram`: The nonogram to render.
+/// * `cell_px`: The width and height of a cell in pixels.
+/// * `palette`: Maps the color of a box to an RGB color.
+/// * `bg`: The RGB color of spaces.
+pub fn to_ppm<T: Copy, F: Fn(T) -> [u8; 3]>(
+    nonogram: &Nonogram<T>,
+    cell_px: usize,
+    palette: F,
+    bg: [u8; 3],
+) -> Vec<u8> {
+    let width = nonogram.cols() * cell_px;
+    let height = nonogram.rows() * cell_px;
+    let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+
+    ppm.reserve(width * height * 3);
+
+    for row in nonogram {
+        let mut line = Vec::with_capacity(width * 3);
+
+        for cell in row {
+            let rgb = match *cell {
+                Cell::Box { color } => palette(color),
+                Cell::Space => bg,
+            };
+            for _ in 0..cell_px {
+                line.extend_from_slice(&rgb);
+            }
+        }
+        for _ in 0..cell_px {
+            ppm.extend_from_slice(&line);
+        }
+    }
+    ppm
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RED: [u8; 3] = [255, 0, 0];
+    const WHITE: [u8; 3] = [255, 255, 255];
+
+    #[test]
+    fn to_ppm_single_box() {
+        let nonogram = Nonogram::try_from(vec![vec![Cell::Box { color: () }]]).unwrap();
+        let ppm = to_ppm(&nonogram, 2, |_| RED, WHITE);
+        let header = b"P6\n2 2\n255\n";
+
+        assert_eq!(header, &ppm[..header.len()]);
+        assert_eq!(RED.repeat(4), &ppm[header.len()..]);
+    }
+
+    #[test]
+    fn to_ppm_cells() {
+        let mut nonogram = Nonogram::new(2, 1);
+        nonogram[(1, 0)] = Cell::Box { color: 'r' };
+
+        let ppm = to_ppm(&nonogram, 1, |_| RED, WHITE);
+        let header = b"P6\n2 1\n255\n";
+
+        assert_eq!(header, &ppm[..header.len()]);
+        assert_eq!([WHITE, RED].concat(), &ppm[header.len()..]);
+    }
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the CLI with the input on stdin.
+fn exec(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_nonogram-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Runs the CLI with the input on stdin and checks that it succeeded.
+fn run(args: &[&str], input: &[u8]) -> Output {
+    let output = exec(args, input);
+
+    assert!(output.status.success());
+    output
+}
+
+#[test]
+fn show_format_pbm() {
+    let solved = run(&["solve"], include_bytes!("../res/apple.json")).stdout;
+    let pbm = String::from_utf8(run(&["show", "--format", "pbm"], &solved).stdout).unwrap();
+    let mut lines = pbm.lines();
+
+    assert_eq!(Some("P1"), lines.next());
+    assert_eq!(Some("10 10"), lines.next());
+
+    let pixels: Vec<&str> = lines.flat_map(str::split_whitespace).collect();
+
+    assert_eq!(100, pixels.len());
+    assert!(pixels.iter().all(|pixel| *pixel == "0" || *pixel == "1"));
+}
+
+#[test]
+fn show_header() {
+    let layout = r#"{"cols": [[["!", 1]], [["!", 1]]], "rows": [[["!", 1]], [["!", 1]]]}"#;
+    let solved = run(&["solve"], layout.as_bytes()).stdout;
+    let output = String::from_utf8(run(&["show"], &solved).stdout).unwrap();
+
+    assert!(output.contains("=== Solution 1/2 (2×2) ==="));
+    assert!(output.contains("=== Solution 2/2 (2×2) ==="));
+
+    let output = String::from_utf8(run(&["show", "--range", "1..2"], &solved).stdout).unwrap();
+
+    assert!(!output.contains("Solution 1/2"));
+    assert!(output.contains("Solution 2/2"));
+}
+
+#[test]
+fn show_grid_wide_clues() {
+    let cols = r#"[[["!", 100]], []]"#;
+    let rows = vec![r#"[["!", 1]]"#; 100].join(", ");
+    let layout = format!(r#"{{"cols": {}, "rows": [{}]}}"#, cols, rows);
+    let solved = run(&["solve"], layout.as_bytes()).stdout;
+    let solutions = String::from_utf8(solved).unwrap();
+    let input = format!("{{\"layout\": {}, \"solutions\": {}}}", layout, solutions);
+    let output = String::from_utf8(run(&["show", "--grid"], input.as_bytes()).stdout).unwrap();
+
+    assert!(output.contains("\n  100  0\n"));
+    assert!(output.contains("███   "));
+}
+
+#[test]
+fn show_index_max() {
+    let layout = r#"{"cols": [[["!", 1]]], "rows": [[["!", 1]]]}"#;
+    let solved = run(&["solve"], layout.as_bytes()).stdout;
+    let index = usize::MAX.to_string();
+    let output = exec(&["show", "--index", &index], &solved);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("out of bounds"));
+}
+
+#[test]
+fn show_grid() {
+    let layout = include_str!("../res/apple.json");
+    let solved = run(&["solve"], layout.as_bytes()).stdout;
+    let solutions = String::from_utf8(solved).unwrap();
+    let input = format!("{{\"layout\": {}, \"solutions\": {}}}", layout, solutions);
+    let output = String::from_utf8(run(&["show", "--grid"], input.as_bytes()).stdout).unwrap();
+
+    assert!(output.contains("2 1 2"));
+    assert!(output.contains(" 1 4 6 7 6 8 1 2 3 4"));
+    assert!(output.contains("██"));
+}
+
+#[test]
+fn count() {
+    let output = run(
+        &["count", "--limit", "10"],
+        include_bytes!("../res/apple.json"),
+    );
+
+    assert_eq!("1", String::from_utf8(output.stdout).unwrap().trim());
+}
+
+#[test]
+fn count_limit_reached() {
+    let input = include_bytes!("../res/apple-color.json");
+    let output = exec(&["count", "--limit", "2", "--json"], input);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!(2, json["count"]);
+    assert_eq!("Full", json["status"]);
+}
+
+#[test]
+fn validate_valid() {
+    let output = exec(&["validate"], include_bytes!("../res/apple.json"));
+
+    assert_eq!(Some(0), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn validate_invalid() {
+    let output = exec(
+        &["validate"],
+        br#"{"cols": [[["a", 3]]], "rows": [[["a", 1]], [["a", 1]]]}"#,
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!(2, stderr.lines().count());
+    assert!(stderr.contains("ColTooLong"));
+    assert!(stderr.contains("SumMismatch"));
+}
+
+#[test]
+fn validate_malformed() {
+    let output = exec(&["validate"], b"{\"cols\": ");
+
+    assert_eq!(Some(2), output.status.code());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn generate_unique() {
+    let args = [
+        "generate", "--count", "5", "--cols", "5", "--rows", "5", "--unique",
+    ];
+    let json = run(&args, &[]).stdout;
+    let layouts: Vec<nonogram_rs::Layout<char>> = serde_json::from_slice(&json).unwrap();
+
+    assert_eq!(5, layouts.len());
+
+    for layout in layouts {
+        assert_eq!(5, layout.col_count());
+        assert!(layout.solve_unique(()).is_ok());
+    }
+}
+
+#[test]
+fn generate_max_attempts() {
+    let args = [
+        "generate",
+        "--count",
+        "2",
+        "--cols",
+        "3",
+        "--rows",
+        "3",
+        "--max-attempts",
+        "1",
+    ];
+    let output = exec(&args, &[]);
+
+    assert!(!output.status.success());
+}
@@ -1,6 +1,14 @@
 #[cfg(feature = "serde")]
 mod demo {
-    use nonogram_rs::Layout;
+    use nonogram_rs::algo::collection::Collector;
+    use nonogram_rs::algo::{Branch, Error};
+    use nonogram_rs::{
+        hint, Difficulty, Item, Layout, Nonogram, PartialNonogram, Puzzle, PuzzleMetadata, Status,
+        UniquenessError,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::SystemTime;
 
     #[test]
     fn apple() {
@@ -10,6 +18,178 @@ mod demo {
         assert_eq!(1, layout.solve(usize::MAX, ()).collection.len());
     }
 
+    #[test]
+    fn apple_add_items() {
+        let json = include_str!("../res/apple.json");
+        let expected: Layout<char> = serde_json::from_str(json).unwrap();
+
+        let cols: [&[usize]; 10] = [
+            &[1],
+            &[1, 4],
+            &[2, 6],
+            &[2, 7],
+            &[1, 6],
+            &[8],
+            &[1, 4, 1],
+            &[4, 2],
+            &[2, 3],
+            &[4],
+        ];
+        let rows: [&[usize]; 10] = [
+            &[2],
+            &[4, 1],
+            &[1, 1],
+            &[2, 1, 2],
+            &[9],
+            &[7, 1],
+            &[9],
+            &[6, 2],
+            &[4, 2],
+            &[5],
+        ];
+        let mut layout = Layout::new_empty(10, 10);
+
+        for (col, lens) in cols.iter().enumerate() {
+            for len in lens.iter() {
+                layout.add_col_item(col, Item::new('!', *len));
+            }
+        }
+        for (row, lens) in rows.iter().enumerate() {
+            for len in lens.iter() {
+                layout.add_row_item(row, Item::new('!', *len));
+            }
+        }
+        assert_eq!(expected, layout);
+        assert_eq!(1, layout.solve(usize::MAX, ()).collection.len());
+    }
+
+    #[test]
+    fn apple_item_count() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(35, layout.item_count());
+        assert_eq!(3, layout.max_items_per_line());
+    }
+
+    #[test]
+    fn apple_count_solutions() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let count = layout.count_solutions(2, ());
+
+        assert_eq!(1, count.count);
+        assert!(matches!(count.status, Status::Complete));
+    }
+
+    #[test]
+    fn apple_solution_clone() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let solution = layout.solve(usize::MAX, ());
+        let mut clone = solution.clone();
+
+        clone.collection.push(Nonogram::new(1, 1));
+
+        assert_eq!(1, solution.len());
+        assert_eq!(2, clone.len());
+        assert_eq!(solution.first(), clone.first());
+    }
+
+    #[test]
+    fn apple_solve_unique() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let expected = layout.clone().solve(1, ()).collection.remove(0);
+
+        assert_eq!(expected, layout.solve_unique(()).unwrap());
+    }
+
+    #[test]
+    fn apple_solve_unique_invalid() {
+        let json = include_str!("../res/apple.json");
+        let mut layout: Layout<char> = serde_json::from_str(json).unwrap();
+        layout.rows[0].push(Item::new('b', 1));
+
+        assert!(matches!(
+            layout.solve_unique(()),
+            Err(UniquenessError::NoSolution)
+        ));
+    }
+
+    #[test]
+    fn apple_solve_partial() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let expected = layout.clone().solve(1, ()).collection.remove(0);
+        let partial = layout.solve_partial(()).unwrap();
+
+        assert!(partial.is_complete);
+        assert_eq!(partial.cols() * partial.rows(), partial.forced_count);
+        assert_eq!(expected, partial.to_nonogram().unwrap());
+    }
+
+    #[test]
+    fn apple_puzzle_round_trip() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let metadata = PuzzleMetadata {
+            title: "Apple".to_string(),
+            author: "Rico Riedel".to_string(),
+            difficulty: Some(Difficulty::Easy),
+            created: Some(SystemTime::UNIX_EPOCH),
+        };
+        let mut puzzle = Puzzle::new(layout, metadata);
+
+        puzzle.solve_and_attach(()).unwrap();
+
+        let json = serde_json::to_string(&puzzle).unwrap();
+        let puzzle: Puzzle<char> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!("Apple", puzzle.metadata.title);
+        assert_eq!(Some(SystemTime::UNIX_EPOCH), puzzle.metadata.created);
+        assert!(puzzle.verify());
+    }
+
+    #[test]
+    fn apple_estimate_difficulty() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert!(layout.estimate_difficulty() <= Difficulty::Medium);
+    }
+
+    #[test]
+    fn apple_is_logic_solvable() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert!(layout.is_logic_solvable());
+    }
+
+    #[test]
+    fn apple_color_is_logic_solvable() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert!(!layout.is_logic_solvable());
+    }
+
+    #[test]
+    fn apple_hint() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let expected = layout.clone().solve(1, ()).collection.remove(0);
+        let mut partial = PartialNonogram::new(expected.cols(), expected.rows());
+
+        while let Some(hint) = hint(&layout, &partial) {
+            assert_eq!(expected[(hint.col, hint.row)], hint.value);
+
+            partial[(hint.col, hint.row)] = hint.value.into();
+        }
+        assert_eq!(PartialNonogram::from(&expected), partial);
+    }
+
     #[test]
     fn apple_color() {
         let json = include_str!("../res/apple-color.json");
@@ -18,6 +198,167 @@ mod demo {
         assert_eq!(3, layout.solve(usize::MAX, ()).collection.len());
     }
 
+    #[test]
+    fn apple_color_solve_exact_limit() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let solution = layout.clone().solve(3, ());
+
+        assert_eq!(3, solution.len());
+        assert!(matches!(solution.status, Status::Complete));
+
+        let solution = layout.solve(2, ());
+
+        assert_eq!(2, solution.len());
+        assert!(matches!(solution.status, Status::Full));
+    }
+
+    #[test]
+    fn apple_color_sorted() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        let mut first = layout.clone().solve(usize::MAX, ());
+        first.sort();
+
+        for _ in 0..10 {
+            let mut solution = layout.clone().solve(usize::MAX, ());
+            solution.sort();
+
+            assert_eq!(first.collection, solution.collection);
+        }
+    }
+
+    /// Counts the propagation rounds of all branches.
+    #[derive(Default)]
+    struct Counter {
+        rounds: AtomicUsize,
+        found: Mutex<Vec<Nonogram<char>>>,
+    }
+
+    impl Collector<char> for Counter {
+        fn push(&self, nonogram: Nonogram<char>) {
+            self.found.lock().unwrap().push(nonogram);
+        }
+
+        fn check(&self) -> Result<(), Error> {
+            self.rounds.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn apple_color_solve_with_hints() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let expected = layout.clone().solve(usize::MAX, ()).collection.remove(0);
+
+        // Reveal every other row of one solution.
+        let known = PartialNonogram::from(&expected);
+        let mut hints = PartialNonogram::new(expected.cols(), expected.rows());
+        for row in (0..expected.rows()).step_by(2) {
+            for col in 0..expected.cols() {
+                hints[(col, row)] = known[(col, row)];
+            }
+        }
+        let solution = layout.clone().solve_with_hints(&hints, usize::MAX, ());
+
+        assert_eq!(vec![expected], solution.collection);
+
+        let cold = Counter::default();
+        Branch::build(layout.cols.clone(), layout.rows.clone()).solve(&cold);
+
+        let warm = Counter::default();
+        let mut branch = Branch::build(layout.cols, layout.rows);
+        for row in 0..hints.rows() {
+            for col in 0..hints.cols() {
+                branch.hint(col, row, hints[(col, row)]).unwrap();
+            }
+        }
+        branch.solve(&warm);
+
+        assert_eq!(1, warm.found.lock().unwrap().len());
+        assert!(warm.rounds.into_inner() < cold.rounds.into_inner());
+    }
+
+    #[test]
+    fn apple_ambiguous_cells() {
+        let json = include_str!("../res/apple.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert!(layout.ambiguous_cells(usize::MAX, ()).is_empty());
+    }
+
+    #[test]
+    fn apple_color_ambiguous_cells() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        let expected = vec![(2, 0), (4, 0), (0, 1), (4, 1), (0, 2), (2, 2), (4, 2)];
+
+        assert_eq!(expected, layout.ambiguous_cells(usize::MAX, ()));
+    }
+
+    #[test]
+    fn apple_color_count_solutions() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let count = layout.clone().count_solutions(usize::MAX, ());
+
+        assert_eq!(3, count.count);
+        assert_eq!(2, layout.count_solutions(2, ()).count);
+    }
+
+    #[test]
+    fn solve_parallel_count() {
+        let apple: Layout<char> = serde_json::from_str(include_str!("../res/apple.json")).unwrap();
+        let apple_color: Layout<char> =
+            serde_json::from_str(include_str!("../res/apple-color.json")).unwrap();
+
+        assert_eq!(1, nonogram_rs::solve_parallel_count(apple, 1, ()));
+        assert_eq!(3, nonogram_rs::solve_parallel_count(apple_color, 4, ()));
+    }
+
+    #[test]
+    fn apple_color_from_nonogram_multi() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let solutions = layout.clone().solve(usize::MAX, ()).collection;
+
+        assert_eq!(3, solutions.len());
+        assert_eq!(Ok(layout), Layout::from_nonogram_multi(&solutions));
+    }
+
+    #[test]
+    #[cfg(feature = "msgpack")]
+    fn apple_color_msgpack() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let bytes = layout.to_msgpack();
+
+        assert_eq!([0x82, 0xa4], bytes[..2]);
+        assert_eq!(b"cols", &bytes[2..6]);
+        assert!(bytes.len() < serde_json::to_vec(&layout).unwrap().len());
+        assert_eq!(layout, Layout::from_msgpack(&bytes).unwrap());
+
+        let solutions = layout.solve(usize::MAX, ()).collection;
+
+        for nonogram in solutions {
+            assert_eq!(
+                nonogram,
+                Nonogram::from_msgpack(&nonogram.to_msgpack()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn apple_color_colors_used() {
+        let json = include_str!("../res/apple-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(vec!['g', 'r', 'y', 'R'], layout.colors_used());
+    }
+
     #[test]
     fn palm() {
         let json = include_str!("../res/palm.json");
@@ -34,6 +375,28 @@ mod demo {
         assert_eq!(2, layout.solve(usize::MAX, ()).collection.len());
     }
 
+    #[test]
+    fn palm_color_solve_unique() {
+        let json = include_str!("../res/palm-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(
+            layout.solve_unique(()),
+            Err(UniquenessError::MultipleSolutions(2))
+        ));
+    }
+
+    #[test]
+    fn palm_color_solve_partial() {
+        let json = include_str!("../res/palm-color.json");
+        let layout: Layout<char> = serde_json::from_str(json).unwrap();
+        let partial = layout.solve_partial(()).unwrap();
+
+        assert!(!partial.is_complete);
+        assert!(partial.forced_count < partial.cols() * partial.rows());
+        assert!(partial.to_nonogram().is_err());
+    }
+
     #[test]
     fn colors() {
         let json = include_str!("../res/colors.json");
@@ -42,6 +405,85 @@ mod demo {
         assert_eq!(1, layout.solve(usize::MAX, ()).collection.len());
     }
 
+    #[cfg(feature = "compact")]
+    fn to_usize(layout: Layout<char>) -> Layout<usize> {
+        let colors = layout.colors_used();
+        let convert = |lines: Vec<Vec<Item<char>>>| {
+            lines
+                .into_iter()
+                .map(|items| {
+                    items
+                        .into_iter()
+                        .map(|item| {
+                            let color = colors.iter().position(|c| *c == item.color).unwrap();
+                            Item::new(color, item.len)
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        Layout::new(convert(layout.cols), convert(layout.rows))
+    }
+
+    #[test]
+    #[cfg(feature = "compact")]
+    fn apple_compact_string() {
+        let json = include_str!("../res/apple.json");
+        let layout = to_usize(serde_json::from_str(json).unwrap());
+
+        assert_eq!(
+            "oaEAEBQCYCcBYIAUEEICMEAgQQEQISCQcQkGIEIFAA",
+            layout.to_compact_string()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "compact")]
+    fn compact_string_round_trip() {
+        let resources = [
+            include_str!("../res/apple.json"),
+            include_str!("../res/apple-color.json"),
+            include_str!("../res/palm.json"),
+            include_str!("../res/palm-color.json"),
+            include_str!("../res/colors.json"),
+            include_str!("../res/flower.json"),
+        ];
+        for json in resources {
+            let layout = to_usize(serde_json::from_str(json).unwrap());
+            let text = layout.to_compact_string();
+
+            assert_eq!(layout, Layout::from_compact_string(&text).unwrap());
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let resources = [
+            include_str!("../res/apple.json"),
+            include_str!("../res/apple-color.json"),
+            include_str!("../res/palm.json"),
+            include_str!("../res/palm-color.json"),
+            include_str!("../res/colors.json"),
+            include_str!("../res/flower.json"),
+        ];
+        for json in resources {
+            let layout: Layout<char> = serde_json::from_str(json).unwrap();
+
+            assert_eq!(layout.sum_cols(), layout.sum_rows());
+            assert!(layout.validate().is_ok());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn flower_from_json_reader() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/res/flower.json");
+        let reader = std::io::BufReader::new(std::fs::File::open(path).unwrap());
+        let layout: Layout<char> = Layout::from_json_reader(reader).unwrap();
+
+        assert_eq!(1, layout.solve(usize::MAX, ()).collection.len());
+    }
+
     #[test]
     fn flower() {
         let json = include_str!("../res/flower.json");